@@ -0,0 +1,368 @@
+use crate::cb::BFRDYN;
+use crate::errors as err;
+
+/// no state / no pattern
+const NIL: u32 = u32::MAX;
+/// the root state
+const ROOT: u32 = 0;
+
+/// which match to report when several patterns match at the same place
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MatchKind {
+    /// leftmost match, ties go to the pattern given first
+    #[default]
+    LeftmostFirst,
+    /// leftmost match, ties go to the longest pattern
+    LeftmostLongest,
+}
+
+/// a pattern found in the haystack
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match {
+    pattern: usize,
+    start: usize,
+    end: usize,
+}
+
+impl Match {
+    /// index of the pattern, in the order patterns were given
+    pub const fn pattern(&self) -> usize { self.pattern }
+    /// byte position where the match start
+    pub const fn start(&self) -> usize { self.start }
+    /// byte position just after the match
+    pub const fn end(&self) -> usize { self.end }
+    /// length of the match in bytes
+    pub const fn len(&self) -> usize { self.end - self.start }
+    /// check if match is empty (never true, empty patterns are not matched)
+    pub const fn is_empty(&self) -> bool { self.end == self.start }
+    /// byte range of the match
+    pub const fn range(&self) -> std::ops::Range<usize> { self.start..self.end }
+}
+
+/// automaton state, the trie node of one pattern prefix
+#[derive(Debug, Clone, Copy)]
+struct State {
+    byte: u8,
+    child: u32,
+    sibling: u32,
+    /// longest proper suffix that is also a pattern prefix
+    fail: u32,
+    /// nearest state on the fail chain that end a pattern
+    dict: u32,
+    /// pattern ending at this state
+    out: u32,
+    depth: u32,
+}
+
+impl State {
+    const fn new(byte: u8, depth: u32) -> Self {
+        Self { byte, child: NIL, sibling: NIL, fail: ROOT, dict: NIL, out: NIL, depth }
+    }
+}
+
+/// AhoCorasick search many patterns at once in one pass over the haystack.
+/// The automaton live in fixed tables:
+/// - STATES is the maximum number of states, at most one per pattern byte
+///   (patterns with the same prefix share states)
+/// - PATTERNS is the maximum number of patterns
+///
+/// It can be built in const context, so it can live in a static.
+/// Empty patterns are accepted but never match.
+/// # example
+/// ```
+/// use cbfr::aho::{AhoCorasick, MatchKind};
+///
+/// const WORDS: AhoCorasick<64, 4> = match AhoCorasick::new(&["error", "warn", "fatal"], MatchKind::LeftmostFirst) {
+///     Ok(ac) => ac,
+///     Err(_) => panic!("too many patterns"),
+/// };
+///
+/// let found: Vec<(usize, usize)> = WORDS.find_iter("warn: fatal error")
+///     .map(|m| (m.pattern(), m.start()))
+///     .collect();
+/// assert_eq!(vec![(1, 0), (2, 6), (0, 12)], found);
+/// ```
+#[derive(Debug, Clone)]
+pub struct AhoCorasick<const STATES: usize = 256, const PATTERNS: usize = 32> {
+    states: [State; STATES],
+    used: usize,
+    patterns: usize,
+    kind: MatchKind,
+}
+
+impl<const STATES: usize, const PATTERNS: usize> AhoCorasick<STATES, PATTERNS> {
+    /// build an automaton from patterns, pattern i is reported as `Match::pattern() == i`.
+    /// Return NotEnoughCapacity when there are more than PATTERNS patterns
+    /// or they need more than STATES states
+    pub const fn new(patterns: &[&str], kind: MatchKind) -> Result<Self, err::NotEnoughCapacity> {
+        let mut ac = Self::empty(kind);
+        let mut i = 0;
+        while i < patterns.len() {
+            if let Err(e) = ac.insert(patterns[i].as_bytes()) { return Err(e); }
+            i += 1;
+        }
+        ac.build_links();
+        Ok(ac)
+    }
+
+    /// build an automaton from any patterns that implement `AsRef<str>` (&str, BFRDYN, String ...)
+    /// # example
+    /// ```
+    /// use cbfr::aho::{AhoCorasick, MatchKind};
+    /// use cbfr::BFRDYN;
+    ///
+    /// let keys: Vec<BFRDYN<16>> = vec!["he".into(), "she".into(), "hers".into()];
+    /// let ac: AhoCorasick = AhoCorasick::from_patterns(&keys, MatchKind::LeftmostLongest).unwrap();
+    ///
+    /// let m = ac.find("ushers").unwrap();
+    /// assert_eq!((1, 1..4), (m.pattern(), m.range()));
+    /// ```
+    ///
+    pub fn from_patterns<I, T>(patterns: I, kind: MatchKind) -> Result<Self, err::NotEnoughCapacity>
+    where I: IntoIterator<Item = T>, T: AsRef<str>
+    {
+        let mut ac = Self::empty(kind);
+        for p in patterns {
+            ac.insert(p.as_ref().as_bytes())?;
+        }
+        ac.build_links();
+        Ok(ac)
+    }
+
+    const fn empty(kind: MatchKind) -> Self {
+        Self {
+            states: [State::new(0, 0); STATES],
+            used: if STATES == 0 { 0 } else { 1 },
+            patterns: 0,
+            kind,
+        }
+    }
+
+    /// get the number of patterns
+    pub const fn patterns_len(&self) -> usize { self.patterns }
+
+    /// get the number of states in use (root included)
+    pub const fn states_used(&self) -> usize { self.used }
+
+    /// get the match semantic
+    pub const fn kind(&self) -> MatchKind { self.kind }
+
+    /// child of a state holding a byte
+    const fn child(&self, state: u32, byte: u8) -> u32 {
+        let mut c = self.states[state as usize].child;
+        while c != NIL {
+            if self.states[c as usize].byte == byte { return c; }
+            c = self.states[c as usize].sibling;
+        }
+        NIL
+    }
+
+    /// add a pattern to the trie
+    const fn insert(&mut self, pattern: &[u8]) -> Result<(), err::NotEnoughCapacity> {
+        if self.patterns == PATTERNS {
+            return Err(err::NotEnoughCapacity::throw(PATTERNS, PATTERNS + 1));
+        }
+        if STATES == 0 { return Err(err::NotEnoughCapacity::throw(STATES, 1)); }
+        let mut state = ROOT;
+        let mut i = 0;
+        while i < pattern.len() {
+            let next = self.child(state, pattern[i]);
+            if next != NIL {
+                state = next;
+            } else {
+                if self.used == STATES {
+                    return Err(err::NotEnoughCapacity::throw(STATES, STATES + pattern.len() - i));
+                }
+                let new = self.used as u32;
+                self.states[new as usize] = State::new(pattern[i], i as u32 + 1);
+                self.states[new as usize].sibling = self.states[state as usize].child;
+                self.states[state as usize].child = new;
+                self.used += 1;
+                state = new;
+            }
+            i += 1;
+        }
+        // the first of duplicated patterns win, empty pattern never match
+        if state != ROOT && self.states[state as usize].out == NIL {
+            self.states[state as usize].out = self.patterns as u32;
+        }
+        self.patterns += 1;
+        Ok(())
+    }
+
+    /// follow a byte from a state, through fail links when there is no edge
+    const fn next_state(&self, mut state: u32, byte: u8) -> u32 {
+        loop {
+            let next = self.child(state, byte);
+            if next != NIL { return next; }
+            if state == ROOT { return ROOT; }
+            state = self.states[state as usize].fail;
+        }
+    }
+
+    /// compute fail and dict links, breadth first
+    const fn build_links(&mut self) {
+        if STATES == 0 { return; }
+        let mut queue = [0u32; STATES];
+        let (mut head, mut tail) = (0, 0);
+        let mut c = self.states[ROOT as usize].child;
+        while c != NIL {
+            self.states[c as usize].fail = ROOT;
+            queue[tail] = c;
+            tail += 1;
+            c = self.states[c as usize].sibling;
+        }
+        while head < tail {
+            let parent = queue[head];
+            head += 1;
+            let mut c = self.states[parent as usize].child;
+            while c != NIL {
+                let fail = self.next_state(self.states[parent as usize].fail, self.states[c as usize].byte);
+                self.states[c as usize].fail = fail;
+                self.states[c as usize].dict = if self.states[fail as usize].out != NIL {
+                    fail
+                } else {
+                    self.states[fail as usize].dict
+                };
+                queue[tail] = c;
+                tail += 1;
+                c = self.states[c as usize].sibling;
+            }
+        }
+    }
+
+    /// true if `new` should replace `best`
+    const fn better(&self, new: &Match, best: &Match) -> bool {
+        if new.start != best.start { return new.start < best.start; }
+        match self.kind {
+            MatchKind::LeftmostFirst => new.pattern < best.pattern,
+            MatchKind::LeftmostLongest => new.end > best.end,
+        }
+    }
+
+    /// find the first match starting at or after `at`
+    const fn find_at(&self, haystack: &[u8], at: usize) -> Option<Match> {
+        if STATES == 0 { return None; }
+        let mut state = ROOT;
+        let mut best: Option<Match> = None;
+        let mut j = at;
+        while j < haystack.len() {
+            state = self.next_state(state, haystack[j]);
+            let depth = self.states[state as usize].depth as usize;
+            // every match not seen yet start at or after the current state prefix
+            if let Some(b) = &best {
+                if j + 1 - depth > b.start { break; }
+            }
+            let mut s = if self.states[state as usize].out != NIL { state } else { self.states[state as usize].dict };
+            while s != NIL {
+                let len = self.states[s as usize].depth as usize;
+                let m = Match { pattern: self.states[s as usize].out as usize, start: j + 1 - len, end: j + 1 };
+                let replace = match &best {
+                    Some(b) => self.better(&m, b),
+                    None => true,
+                };
+                if replace { best = Some(m); }
+                s = self.states[s as usize].dict;
+            }
+            j += 1;
+        }
+        best
+    }
+
+    /// find the first match
+    pub fn find<H: AsRef<[u8]> + ?Sized>(&self, haystack: &H) -> Option<Match> {
+        self.find_at(haystack.as_ref(), 0)
+    }
+
+    /// check if any pattern match
+    pub fn is_match<H: AsRef<[u8]> + ?Sized>(&self, haystack: &H) -> bool {
+        self.find(haystack).is_some()
+    }
+
+    /// iterate over non overlapping matches, left to right
+    /// # example
+    /// ```
+    /// use cbfr::aho::{AhoCorasick, MatchKind};
+    ///
+    /// let pats = ["Sam", "Samwise"];
+    /// let first: AhoCorasick = AhoCorasick::new(&pats, MatchKind::LeftmostFirst).unwrap();
+    /// let longest: AhoCorasick = AhoCorasick::new(&pats, MatchKind::LeftmostLongest).unwrap();
+    ///
+    /// let f: Vec<usize> = first.find_iter("Samwise").map(|m| m.end()).collect();
+    /// let l: Vec<usize> = longest.find_iter("Samwise").map(|m| m.end()).collect();
+    /// assert_eq!(vec![3], f);
+    /// assert_eq!(vec![7], l);
+    /// ```
+    ///
+    pub fn find_iter<'a, 'h, H: AsRef<[u8]> + ?Sized>(&'a self, haystack: &'h H) -> FindIter<'a, 'h, STATES, PATTERNS> {
+        FindIter { ac: self, haystack: haystack.as_ref(), pos: 0 }
+    }
+
+    /// replace every match of pattern i by `replacements[i]` into a new buffer.
+    /// Return NotEnoughCapacity if the result doesn't fit in N bytes
+    /// # Panics
+    /// when there are less replacements than patterns
+    /// # example
+    /// ```
+    /// use cbfr::aho::{AhoCorasick, MatchKind};
+    /// use cbfr::BFRDYN;
+    ///
+    /// let ac: AhoCorasick = AhoCorasick::new(&["cat", "dog"], MatchKind::LeftmostFirst).unwrap();
+    /// let text: BFRDYN<32> = "cat chases dog".into();
+    ///
+    /// let out: BFRDYN<32> = ac.replace_all(&text, &["dog", "cat"]).unwrap();
+    /// assert_eq!("dog chases cat", out.as_str());
+    /// assert!(ac.replace_all::<4>("cat chases dog", &["", ""]).is_err());
+    /// ```
+    ///
+    pub fn replace_all<const N: usize>(&self, haystack: impl AsRef<str>, replacements: &[&str]) -> Result<BFRDYN<N>, err::NotEnoughCapacity> {
+        assert!(replacements.len() >= self.patterns, "one replacement is needed per pattern");
+        self.replace_all_with(haystack, |m| replacements[m.pattern()])
+    }
+
+    /// replace every match by the text returned by `replace` into a new buffer.
+    /// Return NotEnoughCapacity if the result doesn't fit in N bytes
+    /// # example
+    /// ```
+    /// use cbfr::aho::{AhoCorasick, MatchKind};
+    /// use cbfr::BFRDYN;
+    ///
+    /// let ac: AhoCorasick = AhoCorasick::new(&["password", "token"], MatchKind::LeftmostFirst).unwrap();
+    /// let out: BFRDYN<64> = ac.replace_all_with("password=1 token=2", |_| "***").unwrap();
+    /// assert_eq!("***=1 ***=2", out.as_str());
+    /// ```
+    ///
+    pub fn replace_all_with<'r, const N: usize, F>(&self, haystack: impl AsRef<str>, mut replace: F) -> Result<BFRDYN<N>, err::NotEnoughCapacity>
+    where F: FnMut(&Match) -> &'r str
+    {
+        let haystack = haystack.as_ref();
+        let mut out = BFRDYN::<N>::new();
+        let mut last = 0;
+        for m in self.find_iter(haystack) {
+            // patterns are valid utf-8 so matches start and end on char boundaries
+            out.append_str(&haystack[last..m.start()])?;
+            out.append_str(replace(&m))?;
+            last = m.end();
+        }
+        out.append_str(&haystack[last..])?;
+        Ok(out)
+    }
+}
+
+/// Iterator returned by [AhoCorasick::find_iter]
+#[derive(Debug, Clone)]
+pub struct FindIter<'a, 'h, const STATES: usize, const PATTERNS: usize> {
+    ac: &'a AhoCorasick<STATES, PATTERNS>,
+    haystack: &'h [u8],
+    pos: usize,
+}
+
+impl<const STATES: usize, const PATTERNS: usize> Iterator for FindIter<'_, '_, STATES, PATTERNS> {
+    type Item = Match;
+
+    fn next(&mut self) -> Option<Match> {
+        let m = self.ac.find_at(self.haystack, self.pos)?;
+        self.pos = m.end;
+        Some(m)
+    }
+}
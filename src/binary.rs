@@ -0,0 +1,386 @@
+use std::io;
+
+use crate::cb::{BFRDYN, DEFCAPACITY, NecResult};
+use crate::errors as err;
+
+/// BFRBIN is a byte frame for small binary protocols, it hold arbitrary bytes
+/// (no UTF-8 invariant unlike BFRDYN). put_* functions append at the end,
+/// get_* functions read at a cursor that start at 0 and only move forward on success.
+/// Fixed size integers are written in little or big endian, varints in LEB128
+/// and strings are prefixed with their varint length.
+/// # example
+/// ```
+/// use cbfr::BFRBIN;
+///
+/// let mut frame: BFRBIN<32> = BFRBIN::new();
+/// frame.put_u8(1).unwrap();
+/// frame.put_u16_be(0xcafe).unwrap();
+/// frame.put_varint(300).unwrap();
+/// frame.put_str("héllo").unwrap();
+/// assert_eq!(&[1, 0xca, 0xfe, 0xac, 0x02, 6], &frame.as_slice()[..6]);
+///
+/// assert_eq!(1, frame.get_u8().unwrap());
+/// assert_eq!(0xcafe, frame.get_u16_be().unwrap());
+/// assert_eq!(300, frame.get_varint().unwrap());
+/// assert_eq!("héllo", frame.get_str().unwrap());
+/// assert!(frame.get_u8().is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BFRBIN<const CAPACITY: usize = DEFCAPACITY> {
+    arr: [u8; CAPACITY],
+    len: usize,
+    pos: usize,
+}
+
+/// Create binary buffer from bytes, the cursor is at 0
+/// # Example
+/// ```
+/// use cbfr::BFRBIN;
+///
+/// let mut b: BFRBIN<8> = [0x01u8, 0x00, 0xff].as_slice().into();
+/// assert_eq!(1, b.get_u16_le().unwrap());
+/// assert_eq!(0xff, b.get_u8().unwrap());
+/// ```
+/// # Panic
+/// Panic if the slice len > CAPACITY
+///
+impl<const CAPACITY: usize> From<&[u8]> for BFRBIN<CAPACITY> {
+    fn from(value: &[u8]) -> Self {
+        let mut me = Self::new();
+        assert!(value.len() <= CAPACITY, "Not enough capacity!");
+        me.arr[..value.len()].copy_from_slice(value);
+        me.len = value.len();
+        me
+    }
+}
+
+/// Create binary buffer from the bytes of a BFRDYN
+impl<const CAPACITY: usize> From<BFRDYN<CAPACITY>> for BFRBIN<CAPACITY> {
+    fn from(value: BFRDYN<CAPACITY>) -> Self {
+        Self { arr: value.arr, len: value.len(), pos: 0 }
+    }
+}
+
+impl<const CAPACITY: usize> Default for BFRBIN<CAPACITY> {
+    fn default() -> Self { Self::new() }
+}
+
+/// generate put and get for a fixed size integer in both endianness
+macro_rules! fixed_int {
+    ($t:ty, $size:literal, $put_le:ident, $put_be:ident, $get_le:ident, $get_be:ident) => {
+        #[doc = concat!("append a ", stringify!($t), " in little endian")]
+        pub fn $put_le(&mut self, value: $t) -> NecResult {
+            self.put_slice(&value.to_le_bytes())
+        }
+
+        #[doc = concat!("append a ", stringify!($t), " in big endian")]
+        pub fn $put_be(&mut self, value: $t) -> NecResult {
+            self.put_slice(&value.to_be_bytes())
+        }
+
+        #[doc = concat!("read a ", stringify!($t), " in little endian")]
+        pub fn $get_le(&mut self) -> Result<$t, err::InvalidFormat> {
+            Ok(<$t>::from_le_bytes(self.get_array::<$size>()?))
+        }
+
+        #[doc = concat!("read a ", stringify!($t), " in big endian")]
+        pub fn $get_be(&mut self) -> Result<$t, err::InvalidFormat> {
+            Ok(<$t>::from_be_bytes(self.get_array::<$size>()?))
+        }
+    };
+}
+
+impl<const CAPACITY: usize> BFRBIN<CAPACITY> {
+    /// create new empty binary buffer
+    /// # example
+    /// ```
+    /// use cbfr::BFRBIN;
+    /// let b = BFRBIN::<64>::new();
+    /// assert_eq!(0, b.len());
+    /// assert_eq!(64, b.capacity());
+    /// ```
+    ///
+    pub const fn new() -> Self {
+        Self { arr: [0u8; CAPACITY], len: 0, pos: 0 }
+    }
+
+    /// get buffer capacity
+    pub const fn capacity(&self) -> usize { CAPACITY }
+
+    /// get number of bytes written
+    pub const fn len(&self) -> usize { self.len }
+
+    /// check if nothing was written
+    pub const fn is_empty(&self) -> bool { self.len == 0 }
+
+    /// get read cursor position
+    pub const fn position(&self) -> usize { self.pos }
+
+    /// move the read cursor, return InvalidIndex if pos > len
+    /// # example
+    /// ```
+    /// use cbfr::BFRBIN;
+    /// let mut b: BFRBIN<8> = [7u8, 8, 9].as_slice().into();
+    /// b.set_position(2).unwrap();
+    /// assert_eq!(9, b.get_u8().unwrap());
+    /// assert!(b.set_position(4).is_err());
+    /// ```
+    ///
+    pub fn set_position(&mut self, pos: usize) -> Result<(), err::InvalidIndex> {
+        if pos > self.len {
+            return Err(err::InvalidIndex::throw(self.len, pos));
+        }
+        self.pos = pos;
+        Ok(())
+    }
+
+    /// number of bytes left to read
+    pub const fn remaining(&self) -> usize { self.len - self.pos }
+
+    /// all the bytes written
+    pub fn as_slice(&self) -> &[u8] { &self.arr[..self.len] }
+
+    /// the bytes not read yet
+    pub fn unread(&self) -> &[u8] { &self.arr[self.pos..self.len] }
+
+    /// remove all bytes and reset the cursor, unused bytes are reset to 0u8
+    pub fn clear(&mut self) {
+        self.arr[..self.len].fill(0u8);
+        self.len = 0;
+        self.pos = 0;
+    }
+
+    /// append raw bytes
+    pub fn put_slice(&mut self, bytes: &[u8]) -> NecResult {
+        let total = self.len + bytes.len();
+        if total > CAPACITY {
+            return Err(err::NotEnoughCapacity::throw(CAPACITY, total));
+        }
+        self.arr[self.len..total].copy_from_slice(bytes);
+        self.len = total;
+        Ok(())
+    }
+
+    /// append a byte
+    pub fn put_u8(&mut self, value: u8) -> NecResult {
+        self.put_slice(&[value])
+    }
+
+    fixed_int!(u16, 2, put_u16_le, put_u16_be, get_u16_le, get_u16_be);
+    fixed_int!(u32, 4, put_u32_le, put_u32_be, get_u32_le, get_u32_be);
+    fixed_int!(u64, 8, put_u64_le, put_u64_be, get_u64_le, get_u64_be);
+
+    /// append an unsigned LEB128 varint (1 to 10 bytes)
+    /// # example
+    /// ```
+    /// use cbfr::BFRBIN;
+    /// let mut b: BFRBIN<16> = BFRBIN::new();
+    /// b.put_varint(127).unwrap();
+    /// b.put_varint(624485).unwrap();
+    /// assert_eq!(&[0x7f, 0xe5, 0x8e, 0x26], b.as_slice());
+    /// ```
+    ///
+    pub fn put_varint(&mut self, mut value: u64) -> NecResult {
+        let mut tmp = [0u8; 10];
+        let mut n = 0;
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                tmp[n] = byte;
+                n += 1;
+                break;
+            }
+            tmp[n] = byte | 0x80;
+            n += 1;
+        }
+        self.put_slice(&tmp[..n])
+    }
+
+    /// append a signed LEB128 varint (two's complement, as in DWARF and WebAssembly)
+    /// # example
+    /// ```
+    /// use cbfr::BFRBIN;
+    /// let mut b: BFRBIN<16> = BFRBIN::new();
+    /// b.put_varint_signed(-123456).unwrap();
+    /// assert_eq!(&[0xc0, 0xbb, 0x78], b.as_slice());
+    /// assert_eq!(-123456, b.get_varint_signed().unwrap());
+    /// ```
+    ///
+    pub fn put_varint_signed(&mut self, mut value: i64) -> NecResult {
+        let mut tmp = [0u8; 10];
+        let mut n = 0;
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            let done = (value == 0 && byte & 0x40 == 0) || (value == -1 && byte & 0x40 != 0);
+            tmp[n] = if done { byte } else { byte | 0x80 };
+            n += 1;
+            if done { break; }
+        }
+        self.put_slice(&tmp[..n])
+    }
+
+    /// append a string prefixed with its length in bytes as a varint
+    pub fn put_str(&mut self, text: &str) -> NecResult {
+        self.put_bytes(text.as_bytes())
+    }
+
+    /// append bytes prefixed with their length as a varint, the buffer is unchanged on error
+    pub fn put_bytes(&mut self, bytes: &[u8]) -> NecResult {
+        let old = self.len;
+        self.put_varint(bytes.len() as u64)?;
+        self.put_slice(bytes).inspect_err(|_| {
+            self.arr[old..self.len].fill(0u8);
+            self.len = old;
+        })
+    }
+
+    /// read `n` raw bytes
+    pub fn get_slice(&mut self, n: usize) -> Result<&[u8], err::InvalidFormat> {
+        if n > self.remaining() {
+            return Err(err::InvalidFormat::throw(self.len, "unexpected end"));
+        }
+        self.pos += n;
+        Ok(&self.arr[self.pos - n..self.pos])
+    }
+
+    /// read a fixed number of bytes
+    fn get_array<const N: usize>(&mut self) -> Result<[u8; N], err::InvalidFormat> {
+        let mut out = [0u8; N];
+        out.copy_from_slice(self.get_slice(N)?);
+        Ok(out)
+    }
+
+    /// read a byte
+    pub fn get_u8(&mut self) -> Result<u8, err::InvalidFormat> {
+        Ok(self.get_array::<1>()?[0])
+    }
+
+    /// read an unsigned LEB128 varint, error if it is truncated or doesn't fit in u64
+    /// # example
+    /// ```
+    /// use cbfr::BFRBIN;
+    /// let mut b: BFRBIN<16> = [0xe5u8, 0x8e, 0x26, 0x80].as_slice().into();
+    /// assert_eq!(624485, b.get_varint().unwrap());
+    /// let e = b.get_varint().unwrap_err();
+    /// assert_eq!((4, "unexpected end"), (e.index(), e.reason()));
+    /// assert_eq!(3, b.position());
+    /// ```
+    ///
+    pub fn get_varint(&mut self) -> Result<u64, err::InvalidFormat> {
+        let mut value = 0u64;
+        for (i, byte) in self.unread().iter().enumerate() {
+            let at = self.pos + i;
+            let bits = (byte & 0x7f) as u64;
+            // the 10th byte can only hold the top bit of a u64
+            if i == 9 && *byte > 1 {
+                return Err(err::InvalidFormat::throw(at, "varint overflow"));
+            }
+            value |= bits << (7 * i);
+            if byte & 0x80 == 0 {
+                self.pos = at + 1;
+                return Ok(value);
+            }
+        }
+        Err(err::InvalidFormat::throw(self.len, "unexpected end"))
+    }
+
+    /// read a signed LEB128 varint, error if it is truncated or doesn't fit in i64
+    pub fn get_varint_signed(&mut self) -> Result<i64, err::InvalidFormat> {
+        let mut value = 0i64;
+        for (i, byte) in self.unread().iter().enumerate() {
+            let at = self.pos + i;
+            let shift = 7 * i;
+            // the 10th byte only hold bit 63 and its sign extension
+            if i == 9 && *byte != 0 && *byte != 0x7f {
+                return Err(err::InvalidFormat::throw(at, "varint overflow"));
+            }
+            value |= ((byte & 0x7f) as i64) << shift;
+            if byte & 0x80 == 0 {
+                if shift + 7 < 64 && byte & 0x40 != 0 {
+                    value |= -1i64 << (shift + 7);
+                }
+                self.pos = at + 1;
+                return Ok(value);
+            }
+        }
+        Err(err::InvalidFormat::throw(self.len, "unexpected end"))
+    }
+
+    /// read bytes prefixed with their varint length, the cursor doesn't move on error
+    pub fn get_bytes(&mut self) -> Result<&[u8], err::InvalidFormat> {
+        let start = self.pos;
+        let n = self.get_varint()?;
+        if n > self.remaining() as u64 {
+            self.pos = start;
+            return Err(err::InvalidFormat::throw(self.len, "unexpected end"));
+        }
+        self.get_slice(n as usize)
+    }
+
+    /// read a string prefixed with its varint length, the cursor doesn't move on error
+    /// # example
+    /// ```
+    /// use cbfr::BFRBIN;
+    /// let mut b: BFRBIN<16> = [2u8, 0xc3, 0x28].as_slice().into();
+    /// let e = b.get_str().unwrap_err();
+    /// assert_eq!((1, "invalid utf-8"), (e.index(), e.reason()));
+    /// assert_eq!(0, b.position());
+    /// ```
+    ///
+    pub fn get_str(&mut self) -> Result<&str, err::InvalidFormat> {
+        let start = self.pos;
+        let n = self.get_bytes()?.len();
+        let body = self.pos - n;
+        let end = self.pos;
+        if let Err(e) = std::str::from_utf8(&self.arr[body..end]) {
+            self.pos = start;
+            return Err(err::InvalidFormat::throw(body + e.valid_up_to(), "invalid utf-8"));
+        }
+        // SAFETY: validated just above
+        Ok(unsafe { std::str::from_utf8_unchecked(&self.arr[body..end]) })
+    }
+}
+
+/// read consume bytes at the cursor
+/// # example
+/// ```
+/// use std::io::Read;
+/// use cbfr::BFRBIN;
+///
+/// let mut b: BFRBIN<8> = b"abcdef".as_slice().into();
+/// let mut out = [0u8; 4];
+/// b.read_exact(&mut out).unwrap();
+/// assert_eq!(b"abcd", &out);
+/// assert_eq!(b"ef", b.unread());
+/// ```
+///
+impl<const CAPACITY: usize> io::Read for BFRBIN<CAPACITY> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = buf.len().min(self.remaining());
+        buf[..n].copy_from_slice(&self.arr[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+/// write append what fit (write_all fail with WriteZero when full)
+/// # example
+/// ```
+/// use std::io::Write;
+/// use cbfr::BFRBIN;
+///
+/// let mut b: BFRBIN<4> = BFRBIN::new();
+/// assert_eq!(4, b.write(b"abcdef").unwrap());
+/// assert!(b.write_all(b"g").is_err());
+/// ```
+///
+impl<const CAPACITY: usize> io::Write for BFRBIN<CAPACITY> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = buf.len().min(CAPACITY - self.len);
+        self.put_slice(&buf[..n]).ok();
+        Ok(n)
+    }
+    fn flush(&mut self) -> io::Result<()> { Ok(()) }
+}
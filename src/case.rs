@@ -0,0 +1,188 @@
+//! Identifier case conversions. The text is split into words at
+//! - any char that is not a letter or a digit (`_`, `-`, space, `.` ...), which is dropped
+//! - a lowercase to uppercase transition: `fooBar` → `foo` `Bar`
+//! - the end of an uppercase run followed by a lowercase letter: `HTTPServer` → `HTTP` `Server`
+//! - a letter to digit or digit to letter transition: `utf8Decoder` → `utf` `8` `Decoder`
+//!
+//! then written into a new `BFRDYN<N>`, returning NotEnoughCapacity if it doesn't fit.
+//! # example
+//! ```
+//! use cbfr::BFRDYN;
+//!
+//! let b: BFRDYN<32> = "parseHTTPResponse v2".into();
+//! let snake: BFRDYN<32> = b.to_snake_case().unwrap();
+//! assert_eq!("parse_http_response_v_2", snake.as_str());
+//!
+//! let words: Vec<&str> = cbfr::case::words("XMLHttp-request_id").collect();
+//! assert_eq!(vec!["XML", "Http", "request", "id"], words);
+//! ```
+
+use crate::cb::BFRDYN;
+use crate::errors as err;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Class { Sep, Lower, Upper, Digit }
+
+fn class(c: char) -> Class {
+    if c.is_uppercase() { Class::Upper }
+    else if c.is_numeric() { Class::Digit }
+    else if c.is_alphanumeric() { Class::Lower }
+    else { Class::Sep }
+}
+
+/// iterate the words of an identifier or a sentence, see [case](crate::case)
+pub fn words(text: &str) -> Words<'_> {
+    Words { text, pos: 0 }
+}
+
+/// Iterator over words, see [words]
+#[derive(Debug, Clone)]
+pub struct Words<'t> {
+    text: &'t str,
+    pos: usize,
+}
+
+impl<'t> Iterator for Words<'t> {
+    type Item = &'t str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = &self.text[self.pos..];
+        let start = self.pos + rest.find(|c| class(c) != Class::Sep)?;
+        let mut chars = self.text[start..].char_indices().peekable();
+        let mut prev = Class::Sep;
+        let mut end = self.text.len();
+        while let Some((i, c)) = chars.next() {
+            let cur = class(c);
+            let next = chars.peek().map_or(Class::Sep, |(_, n)| class(*n));
+            let boundary = match (prev, cur) {
+                (_, Class::Sep) => true,
+                (Class::Sep, _) => false,
+                (Class::Lower, Class::Upper) => true,
+                (Class::Upper, Class::Upper) => next == Class::Lower,
+                (Class::Digit, Class::Lower | Class::Upper) | (Class::Lower | Class::Upper, Class::Digit) => true,
+                _ => false,
+            };
+            if boundary {
+                end = start + i;
+                break;
+            }
+            prev = cur;
+        }
+        self.pos = end;
+        Some(&self.text[start..end])
+    }
+}
+
+/// how the chars of a word are written
+#[derive(Clone, Copy)]
+enum WordCase { Lower, Upper, Capital }
+
+impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
+    /// join the words with `sep`, first word written with `first`, the others with `rest`
+    fn convert_case<const N: usize>(&self, sep: Option<char>, first: WordCase, rest: WordCase)
+        -> Result<BFRDYN<N>, err::NotEnoughCapacity> {
+        let mut out = BFRDYN::<N>::new();
+        let mut total = 0;
+        for (n, word) in words(self.as_str()).enumerate() {
+            if n > 0 {
+                if let Some(sep) = sep { out.push_char_counted(&mut total, sep); }
+            }
+            let case = if n == 0 { first } else { rest };
+            for (i, c) in word.chars().enumerate() {
+                let upper = match case {
+                    WordCase::Lower => false,
+                    WordCase::Upper => true,
+                    WordCase::Capital => i == 0,
+                };
+                if upper {
+                    c.to_uppercase().for_each(|u| out.push_char_counted(&mut total, u));
+                } else {
+                    c.to_lowercase().for_each(|l| out.push_char_counted(&mut total, l));
+                }
+            }
+        }
+        if total > N {
+            return Err(err::NotEnoughCapacity::throw(N, total));
+        }
+        Ok(out)
+    }
+
+    /// convert to snake_case
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    ///
+    /// let b: BFRDYN<32> = "userAccountID".into();
+    /// assert_eq!("user_account_id", b.to_snake_case::<32>().unwrap().as_str());
+    /// assert!(b.to_snake_case::<8>().is_err());
+    /// ```
+    ///
+    pub fn to_snake_case<const N: usize>(&self) -> Result<BFRDYN<N>, err::NotEnoughCapacity> {
+        self.convert_case(Some('_'), WordCase::Lower, WordCase::Lower)
+    }
+
+    /// convert to camelCase
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    ///
+    /// let b: BFRDYN<32> = "user_account_id".into();
+    /// assert_eq!("userAccountId", b.to_camel_case::<32>().unwrap().as_str());
+    /// ```
+    ///
+    pub fn to_camel_case<const N: usize>(&self) -> Result<BFRDYN<N>, err::NotEnoughCapacity> {
+        self.convert_case(None, WordCase::Lower, WordCase::Capital)
+    }
+
+    /// convert to PascalCase
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    ///
+    /// let b: BFRDYN<32> = "http request-handler".into();
+    /// assert_eq!("HttpRequestHandler", b.to_pascal_case::<32>().unwrap().as_str());
+    /// ```
+    ///
+    pub fn to_pascal_case<const N: usize>(&self) -> Result<BFRDYN<N>, err::NotEnoughCapacity> {
+        self.convert_case(None, WordCase::Capital, WordCase::Capital)
+    }
+
+    /// convert to kebab-case
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    ///
+    /// let b: BFRDYN<32> = "BackgroundColor".into();
+    /// assert_eq!("background-color", b.to_kebab_case::<32>().unwrap().as_str());
+    /// ```
+    ///
+    pub fn to_kebab_case<const N: usize>(&self) -> Result<BFRDYN<N>, err::NotEnoughCapacity> {
+        self.convert_case(Some('-'), WordCase::Lower, WordCase::Lower)
+    }
+
+    /// convert to SCREAMING_SNAKE_CASE
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    ///
+    /// let b: BFRDYN<32> = "maxRetryCount".into();
+    /// assert_eq!("MAX_RETRY_COUNT", b.to_screaming_snake_case::<32>().unwrap().as_str());
+    /// ```
+    ///
+    pub fn to_screaming_snake_case<const N: usize>(&self) -> Result<BFRDYN<N>, err::NotEnoughCapacity> {
+        self.convert_case(Some('_'), WordCase::Upper, WordCase::Upper)
+    }
+
+    /// convert to Train-Case
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    ///
+    /// let b: BFRDYN<32> = "content_type".into();
+    /// assert_eq!("Content-Type", b.to_train_case::<32>().unwrap().as_str());
+    /// ```
+    ///
+    pub fn to_train_case<const N: usize>(&self) -> Result<BFRDYN<N>, err::NotEnoughCapacity> {
+        self.convert_case(Some('-'), WordCase::Capital, WordCase::Capital)
+    }
+}
@@ -1,12 +1,15 @@
 use std::borrow::Borrow;
-use std::borrow::BorrowMut;
 use std::fmt::Display;
+use std::hash::{Hash, Hasher};
 use std::ops::{Add, Sub, Mul, Div};
 use std::mem;
 
 
 use crate::helper as helper;
 use crate::errors as err;
+use crate::search::Finder;
+use crate::pattern::CharPattern;
+use crate::scan;
 
 pub type NecResult = Result<(), err::NotEnoughCapacity>;
 pub type IidxResult = Result<(), err::InvalidIndex>;
@@ -45,16 +48,34 @@ pub const DEFCAPACITY: usize = 256;
 /// assert_eq!(b2.to_string(), "another string");
 /// assert_eq!(b3.to_string(), "more string");
 /// ```
-#[derive(Debug, Hash)]
+#[derive(Debug)]
 pub struct BFRDYN<const CAPACITY: usize = DEFCAPACITY> {
-    arr: [u8; CAPACITY],
-    len: usize
+    pub(crate) arr: [u8; CAPACITY],
+    pub(crate) len: usize
 }
 
 // Display Trait
 impl<const CAPACITY: usize> Display for BFRDYN<CAPACITY> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { 
-        helper::fmt(&self.len, &self.arr, f) 
+        helper::fmt(&self.len, &self.arr, f)
+    }
+}
+
+/// Write formatted text into the buffer, fail (leaving the buffer as is)
+/// when a piece doesn't fit
+/// # Example
+/// ```
+/// use std::fmt::Write;
+/// use cbfr::cb::BFRDYN;
+///
+/// let mut b: BFRDYN<16> = BFRDYN::new();
+/// write!(b, "{}-{:03}", "id", 7).unwrap();
+/// assert_eq!("id-007", b.as_str());
+/// assert!(write!(b, "{}", "way too long for it").is_err());
+/// ```
+impl<const CAPACITY: usize> std::fmt::Write for BFRDYN<CAPACITY> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        self.append_str(s).map_err(|_| std::fmt::Error)
     }
 }
 
@@ -148,7 +169,31 @@ impl<const CAPACITY: usize> From<&[u8]> for BFRDYN<CAPACITY> {
 
 /// clone trait
 impl<const CAPACITY: usize> Clone for BFRDYN<CAPACITY> {
-    fn clone(&self) -> Self { Self { arr: self.arr.clone(), len: self.len.clone() } }
+    fn clone(&self) -> Self { Self { arr: self.arr, len: self.len } }
+}
+
+/// hash trait, buffer is hashed the same way as the &str it holds
+/// so it agree with Borrow<str> (map keyed by BFRDYN can be searched with &str)
+/// # example
+/// ```
+/// use cbfr::cb::BFRDYN;
+/// use std::collections::HashSet;
+///
+/// let mut set: HashSet<BFRDYN<32>> = HashSet::new();
+/// set.insert("some key".into());
+///
+/// assert!(set.contains("some key"));
+/// ```
+///
+impl<const CAPACITY: usize> Hash for BFRDYN<CAPACITY> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+/// default trait, create an empty buffer
+impl<const CAPACITY: usize> Default for BFRDYN<CAPACITY> {
+    fn default() -> Self { Self::new() }
 }
 
 /// partialEQ trait
@@ -164,14 +209,14 @@ impl<const CAPACITY: usize> Clone for BFRDYN<CAPACITY> {
 /// b.append_ch('x');
 /// assert_eq!(false, (a==b));
 ///
+/// // bytes past len are not compared, like Hash and Borrow<str>
+/// unsafe { b.decrease_len(1); }
+/// assert_eq!(true, (a==b));
 /// ```
 ///
 impl<const CAPACITY: usize> PartialEq for BFRDYN<CAPACITY> {
     fn eq(&self, other: &Self) -> bool {
         helper::eq(&self.len, &self.arr, &other.len, &other.arr)
-    } 
-    fn ne(&self, other: &Self) -> bool {
-        helper::ne(&self.len, &self.arr, &other.len, &other.arr)
     }
 }
 
@@ -282,15 +327,9 @@ impl<const CAPACITY: usize> AsRef<[u8]> for BFRDYN<CAPACITY> {
     }
 }
 
-impl<const CAPACITY: usize> Borrow<[u8]> for BFRDYN<CAPACITY> {
-    fn borrow(&self) -> &[u8] {
-        self.as_ref()
-    }
-}
-
-impl<const CAPACITY: usize> BorrowMut<[u8]> for BFRDYN<CAPACITY> {
-    fn borrow_mut(&mut self) -> &mut [u8] {
-        &mut self.arr
+impl<const CAPACITY: usize> Borrow<str> for BFRDYN<CAPACITY> {
+    fn borrow(&self) -> &str {
+        self.as_str()
     }
 }
 
@@ -361,20 +400,16 @@ impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
     /// ```
     ///
     pub const fn from_slice(slice: &[u8]) -> Self {
-        let me = Self::new();
+        let mut me = Self::new();
         assert!(slice.len() <= me.arr.len(), "Not enough capacity");
-        let arr_ptr = me.arr.as_slice().as_ptr() as *mut u8;   //NOTE: WTF...?
-        let len_ptr = (&me.len) as *const usize;
-        unsafe {
-            std::ptr::copy(slice.as_ptr(), arr_ptr, slice.len());
-            std::ptr::copy(&slice.len() as *const usize, len_ptr as *mut usize, 1);
-
-            // if slice.len() <= me.arr.len() {
-            //     std::ptr::copy(slice.as_ptr(), ptr, slice.len())
-            // } else {
-            //     std::ptr::copy(slice.as_ptr(), ptr, me.arr.len())
-            // }
+        // NOTE: writing through a pointer casted from a shared reference
+        // is rejected by const evaluation, copy byte by byte instead
+        let mut i = 0;
+        while i < slice.len() {
+            me.arr[i] = slice[i];
+            i += 1;
         }
+        me.len = slice.len();
         me
     }
 
@@ -415,7 +450,10 @@ impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
     /// ## Here some safe methods you may interested to.
     /// - If you want just a copy of the internal array then use [as_bytes]
     /// - If you want the internal array and no longer need for
-    /// the buffer, then use [take_inner]
+    ///   the buffer, then use [take_inner]
+    /// # Safety
+    /// Writing past the buffer len, or writing bytes that are not valid utf-8,
+    /// leaves the buffer out of sync. Call [auto_len] or [increase_len] afterward.
     /// # Example1 (this is Ok)
     /// ```
     /// use cbfr::cb::BFRDYN;
@@ -492,16 +530,14 @@ impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
     /// assert_eq!(6, b.len());
     /// ```
     pub fn auto_len(&mut self) {
-        let mut newlen = 0;
-        for i in self.arr.iter() {
-            if *i != 0u8 { newlen += 1 }
-            else { break; }
-        }
-        self.len = newlen;
+        self.len = scan::find_byte(&self.arr, 0).unwrap_or(CAPACITY);
     }
 
     /// Manually increase buffer len
-    /// warning: manually modify buffer len is dangerous!. 
+    /// warning: manually modify buffer len is dangerous!.
+    /// # Safety
+    /// The caller must make sure the new len does not exceed the capacity
+    /// and the bytes it covers are valid utf-8.
     /// # Example
     /// ```
     /// use cbfr::cb::BFRDYN;
@@ -519,7 +555,10 @@ impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
     }
 
     /// Manually decrease buffer len
-    /// warning: manually modify buffer len is dangerous!. 
+    /// warning: manually modify buffer len is dangerous!.
+    /// # Safety
+    /// The caller must make sure `by` is not greater than the buffer len
+    /// and the new len lies on a char boundary.
     /// # Example
     /// ```
     /// use cbfr::cb::BFRDYN;
@@ -557,6 +596,18 @@ impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
     /// ```
     pub const fn len(&self) -> usize { self.len }
 
+    /// check if buffer is empty
+    /// # example
+    /// ```
+    /// use cbfr::cb::BFRDYN;
+    /// let a: BFRDYN = "AbCd".into();
+    /// let b: BFRDYN = BFRDYN::def();
+    ///
+    /// assert!(!a.is_empty());
+    /// assert!(b.is_empty());
+    /// ```
+    pub const fn is_empty(&self) -> bool { self.len == 0 }
+
     /// perform checksum to all bytes data inside buffer
     /// # example
     /// ```
@@ -584,10 +635,8 @@ impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
     }
 
     /// Check if buffer contain criteria, return true if yes otherwise return false.
-    /// This function produce the same result with 'contains', the difference is
-    /// this function takes 0(n*m) time complexity while contains takes O(n).
-    /// But this function is const and can be evaluated on compile time
-    /// so this function can run faster (for most of the cases).
+    /// This function produce the same result with 'contains' but it is const
+    /// and can be evaluated on compile time. Both use [Finder] and take O(n+m).
     /// # example
     /// ```
     /// use cbfr::cb::BFRDYN;
@@ -601,6 +650,7 @@ impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
     /// assert!(buf.contain_str("n"));
     /// assert!(buf.contain_str("123"));
     /// assert_eq!(false, buf.contain_str("x"));
+    /// assert!(buf.contain_str(""));
     /// ```
     /// 
     /// # const Example
@@ -615,26 +665,12 @@ impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
     /// ```
     ///
     pub const fn contain_str(&self, criteria: &str) -> bool {
-        let mut i = 0;
-        if criteria.len() > self.len { return false; }
-        let criteria = criteria.as_bytes();
-        while i < (1 + self.len - criteria.len()) {
-            let mut j = 0;
-            let mut current = criteria[j];
-            while current == self.arr[i+j] {
-                j += 1;
-                if j >= criteria.len() { return true; }
-                current = criteria[j];
-            }
-            i += 1;
-        }
-        false
+        Finder::new(criteria).is_in(self.arr.split_at(self.len).0)
     }
 
     /// Check if buffer contain criteria, return true if yes otherwise return false.
-    /// This function produce the same result with 'contain_str', the difference is
-    /// this function takes 0(n) time complexity (contain_str takes O(n*m))
-    /// unfortunately this function is not const.
+    /// This function produce the same result with 'contain_str'.
+    /// To search the same needle many times, build a [Finder] once and use [BFRDYN::find].
     /// # example
     /// ```
     /// use cbfr::cb::BFRDYN;
@@ -651,14 +687,23 @@ impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
     /// ```
     ///
     pub fn contains(&self, criteria: &str) -> bool {
-        if criteria.len() > self.len { return false; }
-        let mut i = 0;
-        while i <= (self.len - criteria.len()) {
-            let current = &self.arr[i..criteria.len()+i];
-            if criteria.as_bytes() == current { return true; } 
-            i += 1;
-        }
-        false
+        Finder::new(criteria).is_in(self.as_ref())
+    }
+
+    /// find the first match of a precompiled needle, return its byte position
+    /// # example
+    /// ```
+    /// use cbfr::cb::BFRDYN;
+    /// use cbfr::search::Finder;
+    ///
+    /// const COLON: Finder = Finder::new(": ");
+    /// let b: BFRDYN<64> = "Content-Type: text/plain".into();
+    ///
+    /// assert_eq!(Some(12), b.find(&COLON));
+    /// ```
+    ///
+    pub const fn find(&self, finder: &Finder) -> Option<usize> {
+        finder.find(self.arr.split_at(self.len).0)
     }
 
     /// get the last value as byte inside a buffer
@@ -723,6 +768,9 @@ impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
     /// buffer self value
     /// This function may panic if you prepend value with len
     /// larger than buffer capacity.
+    /// # Safety
+    /// The caller must make sure the total len of both buffer
+    /// does not exceed the buffer capacity.
     /// # Example
     /// ```
     /// use cbfr::cb::BFRDYN;
@@ -972,11 +1020,9 @@ impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
     pub fn insert(&mut self, other: Self, pos: usize) -> NecResult {
         let total_len = self.len + other.len;
         if total_len <= self.capacity() && pos < self.len {
-            let mut idx = pos;
-            for i in 0..other.len {
+            for (i, idx) in (pos..pos+other.len).enumerate() {
                 self.rshift(idx)?;
                 self.arr[idx] = other.arr[i];
-                idx += 1;
             }
             Ok(())
         } else {
@@ -1019,9 +1065,7 @@ impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
         let mid = self.len/2;
         let mut idx = (0usize, self.len-1);
         while idx.0 < mid {
-            let temp = self.arr[idx.0];
-            self.arr[idx.0] = self.arr[idx.1];
-            self.arr[idx.1] = temp;
+            self.arr.swap(idx.0, idx.1);
             idx.0 += 1;
             idx.1 -= 1;
         }
@@ -1045,9 +1089,7 @@ impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
             for x in 0..i {
                 j = i - x;
                 if self.arr[j] < self.arr[j-1] {
-                    let temp = self.arr[j];
-                    self.arr[j] = self.arr[j-1];
-                    self.arr[j-1] = temp;
+                    self.arr.swap(j, j-1);
                 } else { break; }
             }
         }
@@ -1071,9 +1113,7 @@ impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
             for x in 0..i {
                 j = i - x;
                 if self.arr[j] > self.arr[j-1] {
-                    let temp = self.arr[j];
-                    self.arr[j] = self.arr[j-1];
-                    self.arr[j-1] = temp;
+                    self.arr.swap(j, j-1);
                 } else { break; }
             }
         }
@@ -1097,9 +1137,7 @@ impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
             sorted = true;
             for i in 0..(self.len-1) {
                 if self.arr[i+1] < self.arr[i] {
-                    let temp = self.arr[i];
-                    self.arr[i] = self.arr[i+1];
-                    self.arr[i+1] = temp;
+                    self.arr.swap(i, i+1);
                     sorted = false;
                 }
             }
@@ -1124,16 +1162,15 @@ impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
             sorted = true;
             for i in 0..(self.len-1) {
                 if self.arr[i+1] > self.arr[i] {
-                    let temp = self.arr[i];
-                    self.arr[i] = self.arr[i+1];
-                    self.arr[i+1] = temp;
+                    self.arr.swap(i, i+1);
                     sorted = false;
                 }
             }
         }
     }
 
-    /// trim space on left side
+    /// trim ASCII space on left side, see [trim_start_whitespace](Self::trim_start_whitespace)
+    /// for tabs, newlines and Unicode whitespace
     /// # example
     /// ```
     /// use cbfr::cb::BFRDYN;
@@ -1141,17 +1178,20 @@ impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
     /// b.ltrim();
     /// assert_eq!("L ove", b.as_str());
     /// assert_eq!(5, b.len());
+    ///
+    /// // an all space buffer is trimmed to empty
+    /// let mut spaces: BFRDYN<8> = "   ".into();
+    /// spaces.ltrim();
+    /// assert_eq!("", spaces.as_str());
     /// ```
     ///
     pub fn ltrim(&mut self) {
-        let mut idx = self.len;
-        while self.arr[0] == ' ' as u8 && idx > 1 {
-            self.lshift(0).unwrap();
-            idx -= 1;
-        }
+        let start = scan::find_not_byte(self.as_ref(), b' ').unwrap_or(self.len);
+        self.remove_bytes(0, start);
     }
 
-    /// trim space on right side
+    /// trim ASCII space on right side, see [trim_end_whitespace](Self::trim_end_whitespace)
+    /// for tabs, newlines and Unicode whitespace
     /// # example
     /// ```
     /// use cbfr::cb::BFRDYN;
@@ -1159,16 +1199,23 @@ impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
     /// b.rtrim();
     /// assert_eq!("Lov e", b.as_str());
     /// assert_eq!(5, b.len());
+    ///
+    /// let mut empty: BFRDYN<8> = BFRDYN::new();
+    /// empty.rtrim();
+    /// assert!(empty.is_empty());
+    ///
+    /// let mut spaces: BFRDYN<8> = "   ".into();
+    /// spaces.rtrim();
+    /// assert_eq!("", spaces.as_str());
     /// ```
     ///
     pub fn rtrim(&mut self) {
-        while self.arr[self.len-1] == ' ' as u8 && self.len > 1 {
-            self.arr[self.len-1] = 0u8;
-            self.len -= 1;
-        }
+        let end = scan::rfind_not_byte(self.as_ref(), b' ').map_or(0, |i| i + 1);
+        self.remove_bytes(end, self.len);
     }
 
-    /// trim space
+    /// trim ASCII space, see [trim_whitespace](Self::trim_whitespace)
+    /// for tabs, newlines and Unicode whitespace
     /// # example
     /// ```
     /// use cbfr::cb::BFRDYN;
@@ -1180,6 +1227,143 @@ impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
     ///
     pub fn trim(&mut self) { self.ltrim(); self.rtrim(); }
 
+    /// keep only start..end, in one copy
+    fn keep(&mut self, start: usize, end: usize) {
+        self.remove_bytes(end, self.len);
+        self.remove_bytes(0, start);
+    }
+
+    /// remove every leading and trailing char matching a pattern
+    /// (a char, chars array or slice, or a closure)
+    /// # example
+    /// ```
+    /// use cbfr::cb::BFRDYN;
+    /// let mut b: BFRDYN<256> = "xxabcxyx".into();
+    /// b.trim_matches(['x', 'y']);
+    /// assert_eq!("abc", b.as_str());
+    /// ```
+    ///
+    pub fn trim_matches(&mut self, mut pat: impl CharPattern) {
+        let s = self.as_str();
+        let kept = s.trim_matches(|c| pat.matches(c));
+        let start = kept.as_ptr() as usize - s.as_ptr() as usize;
+        self.keep(start, start + kept.len());
+    }
+
+    /// remove every leading char matching a pattern
+    /// # example
+    /// ```
+    /// use cbfr::cb::BFRDYN;
+    /// let mut b: BFRDYN<256> = "0042".into();
+    /// b.trim_start_matches('0');
+    /// assert_eq!("42", b.as_str());
+    /// ```
+    ///
+    pub fn trim_start_matches(&mut self, mut pat: impl CharPattern) {
+        let kept = self.as_str().trim_start_matches(|c| pat.matches(c)).len();
+        self.keep(self.len - kept, self.len);
+    }
+
+    /// remove every trailing char matching a pattern
+    /// # example
+    /// ```
+    /// use cbfr::cb::BFRDYN;
+    /// let mut b: BFRDYN<256> = "1.500".into();
+    /// b.trim_end_matches('0');
+    /// assert_eq!("1.5", b.as_str());
+    /// ```
+    ///
+    pub fn trim_end_matches(&mut self, mut pat: impl CharPattern) {
+        let kept = self.as_str().trim_end_matches(|c| pat.matches(c)).len();
+        self.keep(0, kept);
+    }
+
+    /// trim leading and trailing Unicode whitespace (tab, newline, no-break space ...)
+    /// # example
+    /// ```
+    /// use cbfr::cb::BFRDYN;
+    /// let mut b: BFRDYN<256> = "\t line\u{a0}\r\n".into();
+    /// b.trim_whitespace();
+    /// assert_eq!("line", b.as_str());
+    /// ```
+    ///
+    pub fn trim_whitespace(&mut self) { self.trim_matches(char::is_whitespace); }
+
+    /// trim leading Unicode whitespace
+    pub fn trim_start_whitespace(&mut self) { self.trim_start_matches(char::is_whitespace); }
+
+    /// trim trailing Unicode whitespace
+    pub fn trim_end_whitespace(&mut self) { self.trim_end_matches(char::is_whitespace); }
+
+    /// remove a prefix if the buffer start with it, return true if it did
+    /// # example
+    /// ```
+    /// use cbfr::cb::BFRDYN;
+    /// let mut b: BFRDYN<256> = "https://example.com".into();
+    /// assert!(b.strip_prefix("https://"));
+    /// assert!(!b.strip_prefix("https://"));
+    /// assert_eq!("example.com", b.as_str());
+    /// ```
+    ///
+    pub fn strip_prefix(&mut self, prefix: &str) -> bool {
+        if !self.as_str().starts_with(prefix) { return false; }
+        self.remove_bytes(0, prefix.len());
+        true
+    }
+
+    /// remove a suffix if the buffer end with it, return true if it did
+    /// # example
+    /// ```
+    /// use cbfr::cb::BFRDYN;
+    /// let mut b: BFRDYN<256> = "report.tar.gz".into();
+    /// assert!(b.strip_suffix(".gz"));
+    /// assert!(!b.strip_suffix(".zip"));
+    /// assert_eq!("report.tar", b.as_str());
+    /// ```
+    ///
+    pub fn strip_suffix(&mut self, suffix: &str) -> bool {
+        if !self.as_str().ends_with(suffix) { return false; }
+        self.remove_bytes(self.len - suffix.len(), self.len);
+        true
+    }
+
+    /// replace every run of Unicode whitespace by a single space, in place and in one pass.
+    /// Leading and trailing runs become one space too, combine with
+    /// [trim_whitespace](Self::trim_whitespace) to drop them
+    /// # example
+    /// ```
+    /// use cbfr::cb::BFRDYN;
+    /// let mut b: BFRDYN<256> = "a \t b\n\n\nc  ".into();
+    /// b.collapse_whitespace();
+    /// assert_eq!("a b c ", b.as_str());
+    /// ```
+    ///
+    pub fn collapse_whitespace(&mut self) {
+        let mut write = 0;
+        let mut read = 0;
+        let mut in_run = false;
+        while read < self.len {
+            let width = helper::utf8_width(self.arr[read]);
+            let c = std::str::from_utf8(&self.arr[read..read + width]).ok()
+                .and_then(|s| s.chars().next())
+                .unwrap_or('\0');
+            if c.is_whitespace() {
+                if !in_run {
+                    self.arr[write] = b' ';
+                    write += 1;
+                }
+                in_run = true;
+            } else {
+                self.arr.copy_within(read..read + width, write);
+                write += width;
+                in_run = false;
+            }
+            read += width;
+        }
+        self.arr[write..self.len].fill(0u8);
+        self.len = write;
+    }
+
     /// convert to lowercase
     /// # example
     /// ```
@@ -1194,7 +1378,7 @@ impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
         for (i, c) in self.arr.iter_mut().enumerate() {
             if i > (self.len-1) { break; }
             if *c <= 90 && *c >= 65 {
-                *c = *c + 32;
+                *c += 32;
             }
         }
 
@@ -1220,7 +1404,7 @@ impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
         for (i, c) in self.arr.iter_mut().enumerate() {
             if i > (self.len-1) { break; }
             if *c >= 97 && *c <= 122 {
-                *c = *c - 32;
+                *c -= 32;
             }
         }
 
@@ -1263,19 +1447,22 @@ impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
         let mut change_next = false;
         self.title();
         for (idx, c) in self.arr.iter_mut().enumerate() {
-            if change_next && *c != ' ' as u8 {
-                *c = *c - 32;
+            if change_next && *c != b' ' {
+                *c -= 32;
                 change_next = false;
             }
-            if idx < self.len-1 && *c == ' ' as u8 {
+            if idx < self.len-1 && *c == b' ' {
                 change_next = true;
             }
         }
     }
 
     /// get slice without checking if 'start' and 'end' is a valid index
-    /// this function may return unexpected result if 
+    /// this function may return unexpected result if
     /// the start or end value lies beyond valid index
+    /// # Safety
+    /// The caller must make sure `start <= end <= len`,
+    /// otherwise the slice may contain stale bytes or panic.
     /// # Example
     /// ```
     /// use cbfr::cb::BFRDYN;
@@ -1339,15 +1526,13 @@ impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
     pub fn to_vecir(&self, c: char) -> Vec<String> {
         let mut v = Vec::<String>::new();
         let mut start: usize = 0;
-        let mut end: usize = 0;
-        for i in self.arr {
-            end += 1;
-            if i == c as u8 {
-                let x = &self.arr[start..end-1];
-                let bfr: BFRDYN<CAPACITY> = x.into();
-                v.push(bfr.to_string());
-                start = end-1;
-            }
+        let mut from: usize = 0;
+        while let Some((i, w)) = self.find_char_from(from, c) {
+            let x = &self.arr[start..i];
+            let bfr: BFRDYN<CAPACITY> = x.into();
+            v.push(bfr.to_string());
+            start = i;
+            from = i+w;
         }
         let last = &self.arr[start..self.len];
         let bfr: BFRDYN<CAPACITY> = last.into();
@@ -1371,15 +1556,11 @@ impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
     pub fn to_vecil(&self, c: char) -> Vec<String> {
         let mut v = Vec::<String>::new();
         let mut start: usize = 0;
-        let mut end: usize = 0;
-        for i in self.arr {
-            end += 1;
-            if i == c as u8 {
-                let x = &self.arr[start..end];
-                let bfr: BFRDYN<CAPACITY> = x.into();
-                v.push(bfr.to_string());
-                start = end;
-            }
+        while let Some((i, w)) = self.find_char_from(start, c) {
+            let x = &self.arr[start..i+w];
+            let bfr: BFRDYN<CAPACITY> = x.into();
+            v.push(bfr.to_string());
+            start = i+w;
         }
         let last = &self.arr[start..self.len];
         let bfr: BFRDYN<CAPACITY> = last.into();
@@ -1398,20 +1579,20 @@ impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
     /// assert_eq!("you", words.pop().unwrap());
     /// assert_eq!("love", words.pop().unwrap());
     /// assert_eq!("I", words.pop().unwrap());
+    ///
+    /// // 'é' is the byte 0xE9 when cast to u8, a lead byte of '香' (E9 A6 99)
+    /// let b: BFRDYN<32> = "香éx".into();
+    /// assert_eq!(vec!["香", "x"], b.to_vec('é'));
     /// ```
     ///
     pub fn to_vec(&self, c: char) -> Vec<String> {
         let mut v = Vec::<String>::new();
         let mut start: usize = 0;
-        let mut end: usize = 0;
-        for i in self.arr {
-            end += 1;
-            if i == c as u8 {
-                let x = &self.arr[start..end-1];
-                let bfr: BFRDYN<CAPACITY> = x.into();
-                v.push(bfr.to_string());
-                start = end;
-            }
+        while let Some((i, w)) = self.find_char_from(start, c) {
+            let x = &self.arr[start..i];
+            let bfr: BFRDYN<CAPACITY> = x.into();
+            v.push(bfr.to_string());
+            start = i+w;
         }
         let last = &self.arr[start..self.len];
         let bfr: BFRDYN<CAPACITY> = last.into();
@@ -1435,14 +1616,11 @@ impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
     pub fn to_vec2(&self, s: &str) -> Vec<String> {
         let mut v = Vec::<String>::new();
         let mut start: usize = 0;
-        for (i, _) in self.arr.iter().enumerate() {
-            if i + s.len() > self.len { break; }
-            if self.arr[i..i+s.len()] == s.as_bytes()[..] {
-                let x = &self.arr[start..i];
-                let bfr: BFRDYN<CAPACITY> = x.into();
-                v.push(bfr.to_string());
-                start = i+s.len();
-            }
+        for i in Finder::new(s).find_iter(self.as_ref()) {
+            let x = &self.arr[start..i];
+            let bfr: BFRDYN<CAPACITY> = x.into();
+            v.push(bfr.to_string());
+            start = i+s.len();
         }
         let last = &self.arr[start..self.len];
         let bfr: BFRDYN<CAPACITY> = last.into();
@@ -1466,15 +1644,19 @@ impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
     pub fn to_vecr(&self, s: &str, r: char) -> Vec<String> {
         let mut v = Vec::<String>::new();
         let mut start: usize = 0;
-        for (i, _) in self.arr.iter().enumerate() {
-            if i + s.len() > self.len { break; }
+        let finder = Finder::new(s);
+        let mut from = 0;
+        while let Some(i) = finder.find_at(self.as_ref(), from) {
             let end = i+s.len();
-            if (self.arr[i..end] == s.as_bytes()[..]) && self.arr[end..end+1][0] != r as u8 {
-                let x = &self.arr[start..i];
-                let bfr: BFRDYN<CAPACITY> = x.into();
-                v.push(bfr.to_string());
-                start = i+s.len();
+            if end < self.len && self.arr[end] == r as u8 {
+                from = i+1;
+                continue;
             }
+            let x = &self.arr[start..i];
+            let bfr: BFRDYN<CAPACITY> = x.into();
+            v.push(bfr.to_string());
+            start = end;
+            from = end.max(i+1);
         }
         let last = &self.arr[start..self.len];
         let bfr: BFRDYN<CAPACITY> = last.into();
@@ -1529,14 +1711,10 @@ impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
     /// ```
     ///
     pub fn split(self, criteria: char) -> Option<(Self, Self)> {
-        for (i, c) in self.arr.iter().enumerate() {
-            if *c as char == criteria {
-                let left: Self = self.arr[0..i].into();
-                let right: Self = self.arr[i+1..self.len].into();
-                return Some((left, right));
-            }
-        }
-        None
+        let (i, w) = self.find_char(criteria)?;
+        let left: Self = self.arr[0..i].into();
+        let right: Self = self.arr[i+w..self.len].into();
+        Some((left, right))
     }
     /// split by &char (include the criteria) and return Option<(Self, Self)>
     /// or return None if the criteria didn't match
@@ -1552,14 +1730,10 @@ impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
     /// ```
     ///
     pub fn split_incl(self, criteria: char) -> Option<(Self, Self)> {
-        for (i, c) in self.arr.iter().enumerate() {
-            if *c as char == criteria {
-                let left: Self = self.arr[0..i].into();
-                let right: Self = self.arr[i..self.len].into();
-                return Some((left, right));
-            }
-        }
-        None
+        let (i, _) = self.find_char(criteria)?;
+        let left: Self = self.arr[0..i].into();
+        let right: Self = self.arr[i..self.len].into();
+        Some((left, right))
     }
     /// split by &char (include the criteria to the left) and return Option<(Self, Self)>
     /// or return None if the criteria didn't match
@@ -1575,14 +1749,10 @@ impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
     /// ```
     ///
     pub fn split_incl_left(self, criteria: char) -> Option<(Self, Self)> {
-        for (i, c) in self.arr.iter().enumerate() {
-            if *c as char == criteria {
-                let left: Self = self.arr[0..i+1].into();
-                let right: Self = self.arr[i+1..self.len].into();
-                return Some((left, right));
-            }
-        }
-        None
+        let (i, w) = self.find_char(criteria)?;
+        let left: Self = self.arr[0..i+w].into();
+        let right: Self = self.arr[i+w..self.len].into();
+        Some((left, right))
     }
     /// split by &str (exclude the criteria) and return Option<(Self, Self)>
     /// or return None if the criteria didn't match
@@ -1598,17 +1768,12 @@ impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
     /// ```
     ///
     pub fn split2(self, criteria: &str) -> Option<(Self, Self)> {
-        for (i, _) in self.arr.iter().enumerate() {
-            if i + criteria.len() > self.len { break; }
-            if self.arr[i..i+criteria.len()] == criteria.as_bytes()[..] {
-                let left = &self.arr[0..i];
-                let right = &self.arr[i+criteria.len()..self.len];
-                let lf: Self = left.into();
-                let rg: Self = right.into();
-                return Some((lf, rg))
-            }
-        }
-        None
+        let i = Finder::new(criteria).find(self.as_ref())?;
+        let left = &self.arr[0..i];
+        let right = &self.arr[i+criteria.len()..self.len];
+        let lf: Self = left.into();
+        let rg: Self = right.into();
+        Some((lf, rg))
     }
     /// split by &str (include the criteria) and return Option<(Self, Self)>
     /// or return None if the criteria didn't match
@@ -1624,17 +1789,12 @@ impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
     /// ```
     ///
     pub fn split2_incl(self, criteria: &str) -> Option<(Self, Self)> {
-        for (i, _) in self.arr.iter().enumerate() {
-            if i + criteria.len() > self.len { break; }
-            if self.arr[i..i+criteria.len()] == criteria.as_bytes()[..] {
-                let left = &self.arr[0..i];
-                let right = &self.arr[i..self.len];
-                let lf: Self = left.into();
-                let rg: Self = right.into();
-                return Some((lf, rg))
-            }
-        }
-        None
+        let i = Finder::new(criteria).find(self.as_ref())?;
+        let left = &self.arr[0..i];
+        let right = &self.arr[i..self.len];
+        let lf: Self = left.into();
+        let rg: Self = right.into();
+        Some((lf, rg))
     }
     /// split by &str (include the criteria to the left) and return Option<(Self, Self)>
     /// or return None if the criteria didn't match
@@ -1650,17 +1810,12 @@ impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
     /// ```
     ///
     pub fn split2_incl_left(self, criteria: &str) -> Option<(Self, Self)> {
-        for (i, _) in self.arr.iter().enumerate() {
-            if i + criteria.len() > self.len { break; }
-            if self.arr[i..i+criteria.len()] == criteria.as_bytes()[..] {
-                let left = &self.arr[0..i+criteria.len()];
-                let right = &self.arr[i+criteria.len()..self.len];
-                let lf: Self = left.into();
-                let rg: Self = right.into();
-                return Some((lf, rg))
-            }
-        }
-        None
+        let i = Finder::new(criteria).find(self.as_ref())?;
+        let left = &self.arr[0..i+criteria.len()];
+        let right = &self.arr[i+criteria.len()..self.len];
+        let lf: Self = left.into();
+        let rg: Self = right.into();
+        Some((lf, rg))
     }
     /// evaluate if buffer starts_with with criteria 
     /// # example
@@ -1775,3 +1930,53 @@ impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
     }
 }
 
+// crate internal implementations
+// callers must check capacity, bounds and char boundaries
+impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
+    /// insert bytes at pos, shifting the rest to the right in one copy
+    pub(crate) fn insert_bytes(&mut self, pos: usize, bytes: &[u8]) {
+        self.arr.copy_within(pos..self.len, pos + bytes.len());
+        self.arr[pos..pos + bytes.len()].copy_from_slice(bytes);
+        self.len += bytes.len();
+    }
+
+    /// position and utf-8 width of the first occurrence of a char
+    pub(crate) fn find_char(&self, c: char) -> Option<(usize, usize)> {
+        self.find_char_from(0, c)
+    }
+
+    /// find_char starting at byte `from`, the position returned is from the buffer start
+    pub(crate) fn find_char_from(&self, from: usize, c: char) -> Option<(usize, usize)> {
+        let haystack = &self.arr[from..self.len];
+        if c.is_ascii() {
+            return scan::find_byte(haystack, c as u8).map(|i| (from + i, 1));
+        }
+        let mut tmp = [0u8; 4];
+        let needle = c.encode_utf8(&mut tmp);
+        Finder::new(needle).find(haystack).map(|i| (from + i, needle.len()))
+    }
+
+    /// append bytes while `total` (bytes wanted so far) still fit, always count them.
+    /// Build an output then report NotEnoughCapacity with the total length if it overflowed
+    pub(crate) fn push_counted(&mut self, total: &mut usize, bytes: &[u8]) {
+        if *total == self.len && self.len + bytes.len() <= CAPACITY {
+            self.arr[self.len..self.len + bytes.len()].copy_from_slice(bytes);
+            self.len += bytes.len();
+        }
+        *total += bytes.len();
+    }
+
+    /// push_counted for a char
+    pub(crate) fn push_char_counted(&mut self, total: &mut usize, c: char) {
+        let mut tmp = [0u8; 4];
+        self.push_counted(total, c.encode_utf8(&mut tmp).as_bytes());
+    }
+
+    /// remove bytes in start..end, unused bytes are reset to 0u8
+    pub(crate) fn remove_bytes(&mut self, start: usize, end: usize) {
+        self.arr.copy_within(end..self.len, start);
+        let newlen = self.len - (end - start);
+        self.arr[newlen..self.len].fill(0u8);
+        self.len = newlen;
+    }
+}
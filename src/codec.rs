@@ -0,0 +1,340 @@
+//! Hex, Base64 and Base32 (RFC 4648) encoding of bytes into a BFRDYN and decoding back into a byte buffer.
+//! Each [Encoding] give the exact output capacity at compile time with [Encoding::encoded_len]
+//! and [Encoding::decoded_len].
+//!
+//! Decoding doesn't branch nor index a table on the input symbols, so its timing only depend
+//! on the input length and is safe for secret material (keys, tokens).
+//! # example
+//! ```
+//! use cbfr::BFRDYN;
+//! use cbfr::codec::{self, Encoding};
+//!
+//! const KEY_LEN: usize = Encoding::encoded_len(&codec::BASE64_URL_NO_PAD, 16);
+//! let key = [0xfbu8; 16];
+//! let token: BFRDYN<KEY_LEN> = BFRDYN::encode_bytes(&key, &codec::BASE64_URL_NO_PAD).unwrap();
+//! assert_eq!("-_v7-_v7-_v7-_v7-_v7-w", token.as_str());
+//!
+//! let mut back = [0u8; 16];
+//! let n = token.decode_bytes(&codec::BASE64_URL_NO_PAD, &mut back).unwrap();
+//! assert_eq!(&key, &back[..n]);
+//! ```
+
+use crate::cb::BFRDYN;
+use crate::errors as err;
+
+/// A binary to text encoding: alphabet, bits per symbol and padding
+#[derive(Debug, Clone, Copy)]
+pub struct Encoding {
+    symbols: &'static [u8],
+    bits: usize,
+    pad: bool,
+    value: fn(i16) -> i16,
+}
+
+/// hex with lowercase digits, decoding accept both cases
+pub const HEX: Encoding = Encoding { symbols: b"0123456789abcdef", bits: 4, pad: false, value: hex_value };
+/// hex with uppercase digits, decoding accept both cases
+pub const HEX_UPPER: Encoding = Encoding { symbols: b"0123456789ABCDEF", ..HEX };
+/// standard Base64 with `=` padding
+pub const BASE64: Encoding = Encoding {
+    symbols: b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/",
+    bits: 6,
+    pad: true,
+    value: base64_value,
+};
+/// standard Base64 without padding
+pub const BASE64_NO_PAD: Encoding = Encoding { pad: false, ..BASE64 };
+/// URL and filename safe Base64 (`-` and `_`) with `=` padding
+pub const BASE64_URL: Encoding = Encoding {
+    symbols: b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_",
+    value: base64_url_value,
+    ..BASE64
+};
+/// URL and filename safe Base64 without padding
+pub const BASE64_URL_NO_PAD: Encoding = Encoding { pad: false, ..BASE64_URL };
+/// Base32 with `=` padding
+pub const BASE32: Encoding = Encoding { symbols: b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567", bits: 5, pad: true, value: base32_value };
+/// Base32 without padding
+pub const BASE32_NO_PAD: Encoding = Encoding { pad: false, ..BASE32 };
+
+const PAD: u8 = b'=';
+
+/// -1 when lo <= c <= hi, 0 otherwise, without branch
+const fn in_range(c: i16, lo: u8, hi: u8) -> i16 {
+    ((lo as i16 - 1 - c) & (c - hi as i16 - 1)) >> 15
+}
+
+// the symbol values, -1 for an invalid symbol. Each range add its value + 1 when c is in it
+
+fn hex_value(c: i16) -> i16 {
+    -1 + (in_range(c, b'0', b'9') & (c - b'0' as i16 + 1))
+        + (in_range(c, b'a', b'f') & (c - b'a' as i16 + 11))
+        + (in_range(c, b'A', b'F') & (c - b'A' as i16 + 11))
+}
+
+fn base64_alpha(c: i16) -> i16 {
+    -1 + (in_range(c, b'A', b'Z') & (c - b'A' as i16 + 1))
+        + (in_range(c, b'a', b'z') & (c - b'a' as i16 + 27))
+        + (in_range(c, b'0', b'9') & (c - b'0' as i16 + 53))
+}
+
+fn base64_value(c: i16) -> i16 {
+    base64_alpha(c) + (in_range(c, b'+', b'+') & 63) + (in_range(c, b'/', b'/') & 64)
+}
+
+fn base64_url_value(c: i16) -> i16 {
+    base64_alpha(c) + (in_range(c, b'-', b'-') & 63) + (in_range(c, b'_', b'_') & 64)
+}
+
+fn base32_value(c: i16) -> i16 {
+    -1 + (in_range(c, b'A', b'Z') & (c - b'A' as i16 + 1))
+        + (in_range(c, b'2', b'7') & (c - b'2' as i16 + 27))
+}
+
+impl Encoding {
+    /// symbols in a padded group: 2 for hex, 4 for Base64, 8 for Base32
+    const fn group(&self) -> usize {
+        match self.bits { 4 => 2, 6 => 4, _ => 8 }
+    }
+
+    /// exact number of symbols to encode `n` bytes
+    /// # example
+    /// ```
+    /// use cbfr::codec::{self, Encoding};
+    ///
+    /// assert_eq!(64, codec::HEX.encoded_len(32));
+    /// assert_eq!(44, codec::BASE64.encoded_len(32));
+    /// assert_eq!(43, codec::BASE64_NO_PAD.encoded_len(32));
+    /// assert_eq!(56, Encoding::encoded_len(&codec::BASE32, 32));
+    /// ```
+    ///
+    pub const fn encoded_len(&self, n: usize) -> usize {
+        let symbols = (n * 8).div_ceil(self.bits);
+        if self.pad { symbols.next_multiple_of(self.group()) } else { symbols }
+    }
+
+    /// maximum number of bytes decoded from `n` symbols, the size of a decoding buffer
+    /// # example
+    /// ```
+    /// use cbfr::codec;
+    ///
+    /// assert_eq!(32, codec::HEX.decoded_len(64));
+    /// assert_eq!(33, codec::BASE64.decoded_len(44));
+    /// assert_eq!(32, codec::BASE64_NO_PAD.decoded_len(43));
+    /// ```
+    ///
+    pub const fn decoded_len(&self, n: usize) -> usize {
+        n * self.bits / 8
+    }
+
+    /// check a count of trailing symbols (not a whole group) can be produced by the encoder
+    const fn valid_tail(&self, r: usize) -> bool {
+        r == 0 || r * self.bits / 8 > (r - 1) * self.bits / 8
+    }
+}
+
+impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
+    /// encode bytes with an [Encoding]
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    /// use cbfr::codec;
+    ///
+    /// let b: BFRDYN<16> = BFRDYN::encode_bytes(b"foob", &codec::BASE32_NO_PAD).unwrap();
+    /// assert_eq!("MZXW6YQ", b.as_str());
+    /// assert!(BFRDYN::<7>::encode_bytes(b"foob", &codec::BASE32).is_err());
+    /// ```
+    ///
+    pub fn encode_bytes(bytes: &[u8], enc: &Encoding) -> Result<Self, err::NotEnoughCapacity> {
+        let total = enc.encoded_len(bytes.len());
+        if total > CAPACITY {
+            return Err(err::NotEnoughCapacity::throw(CAPACITY, total));
+        }
+        let mut out = Self::new();
+        let mask = (1u32 << enc.bits) - 1;
+        let mut acc = 0u32;
+        let mut nbits = 0;
+        for b in bytes {
+            acc = (acc << 8) | *b as u32;
+            nbits += 8;
+            while nbits >= enc.bits {
+                nbits -= enc.bits;
+                out.arr[out.len] = enc.symbols[((acc >> nbits) & mask) as usize];
+                out.len += 1;
+            }
+        }
+        if nbits > 0 {
+            out.arr[out.len] = enc.symbols[((acc << (enc.bits - nbits)) & mask) as usize];
+            out.len += 1;
+        }
+        out.arr[out.len..total].fill(PAD);
+        out.len = total;
+        Ok(out)
+    }
+
+    /// lowercase hex of bytes
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    ///
+    /// let b: BFRDYN<8> = BFRDYN::encode_hex(&[0xde, 0xad, 0x0b]).unwrap();
+    /// assert_eq!("dead0b", b.as_str());
+    /// ```
+    ///
+    pub fn encode_hex(bytes: &[u8]) -> Result<Self, err::NotEnoughCapacity> {
+        Self::encode_bytes(bytes, &HEX)
+    }
+
+    /// uppercase hex of bytes
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    ///
+    /// let b: BFRDYN<8> = BFRDYN::encode_hex_upper(&[0xde, 0xad, 0x0b]).unwrap();
+    /// assert_eq!("DEAD0B", b.as_str());
+    /// ```
+    ///
+    pub fn encode_hex_upper(bytes: &[u8]) -> Result<Self, err::NotEnoughCapacity> {
+        Self::encode_bytes(bytes, &HEX_UPPER)
+    }
+
+    /// standard padded Base64 of bytes, use [encode_bytes](Self::encode_bytes) for the other variants
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    ///
+    /// let b: BFRDYN<16> = BFRDYN::encode_base64(b"hello").unwrap();
+    /// assert_eq!("aGVsbG8=", b.as_str());
+    /// ```
+    ///
+    pub fn encode_base64(bytes: &[u8]) -> Result<Self, err::NotEnoughCapacity> {
+        Self::encode_bytes(bytes, &BASE64)
+    }
+
+    /// padded Base32 of bytes
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    ///
+    /// let b: BFRDYN<16> = BFRDYN::encode_base32(b"hello").unwrap();
+    /// assert_eq!("NBSWY3DP", b.as_str());
+    /// ```
+    ///
+    pub fn encode_base32(bytes: &[u8]) -> Result<Self, err::NotEnoughCapacity> {
+        Self::encode_bytes(bytes, &BASE32)
+    }
+
+    /// decode the buffer with an [Encoding] into `out`, return the number of bytes written.
+    /// Padding must be present for padded encodings and absent otherwise, and the unused
+    /// bits of the last symbol must be zero. Runs in time depending only on the length
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    /// use cbfr::codec;
+    ///
+    /// let b: BFRDYN<16> = "aGVsbG8=".into();
+    /// let mut out = [0u8; 8];
+    /// let n = b.decode_bytes(&codec::BASE64, &mut out).unwrap();
+    /// assert_eq!(b"hello", &out[..n]);
+    ///
+    /// let bad: BFRDYN<16> = "aGV$bG8=".into();
+    /// let e = bad.decode_bytes(&codec::BASE64, &mut out).unwrap_err();
+    /// assert_eq!((3, "invalid symbol"), (e.index(), e.reason()));
+    ///
+    /// let unpadded: BFRDYN<16> = "aGVsbG8".into();
+    /// assert_eq!(7, unpadded.decode_bytes(&codec::BASE64, &mut out).unwrap_err().index());
+    /// ```
+    ///
+    pub fn decode_bytes(&self, enc: &Encoding, out: &mut [u8]) -> Result<usize, err::InvalidFormat> {
+        let input = &self.arr[..self.len];
+        let group = enc.group();
+        let mut len = input.len();
+        if enc.pad {
+            if len % group != 0 {
+                return Err(err::InvalidFormat::throw(len, "invalid length"));
+            }
+            let mut pads = 0;
+            while pads < group - 1 && len > 0 && input[len - 1] == PAD {
+                len -= 1;
+                pads += 1;
+            }
+        }
+        if !enc.valid_tail(len % group) {
+            return Err(err::InvalidFormat::throw(len, "invalid length"));
+        }
+        let need = enc.decoded_len(len);
+        if need > out.len() {
+            return Err(err::InvalidFormat::throw(out.len() * 8 / enc.bits, "output is full"));
+        }
+        let mask = (1u32 << enc.bits) - 1;
+        let mut acc = 0u32;
+        let mut nbits = 0;
+        let mut written = 0;
+        let mut first_bad = usize::MAX;
+        for (i, c) in input[..len].iter().enumerate() {
+            let v = (enc.value)(*c as i16);
+            // all ones when the symbol is invalid
+            let bad = (v >> 15) as isize as usize;
+            first_bad = first_bad.min(i | !bad);
+            acc = (acc << enc.bits) | (v as u32 & mask);
+            nbits += enc.bits;
+            if nbits >= 8 {
+                nbits -= 8;
+                out[written] = (acc >> nbits) as u8;
+                written += 1;
+            }
+        }
+        if first_bad != usize::MAX {
+            return Err(err::InvalidFormat::throw(first_bad, "invalid symbol"));
+        }
+        if acc & ((1 << nbits) - 1) != 0 {
+            return Err(err::InvalidFormat::throw(len - 1, "non zero trailing bits"));
+        }
+        Ok(written)
+    }
+
+    /// decode hex (either case) into `out`, return the number of bytes written
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    ///
+    /// let b: BFRDYN<8> = "C0ffee".into();
+    /// let mut out = [0u8; 3];
+    /// assert_eq!(3, b.decode_hex(&mut out).unwrap());
+    /// assert_eq!([0xc0, 0xff, 0xee], out);
+    /// ```
+    ///
+    pub fn decode_hex(&self, out: &mut [u8]) -> Result<usize, err::InvalidFormat> {
+        self.decode_bytes(&HEX, out)
+    }
+
+    /// decode standard padded Base64 into `out`, return the number of bytes written
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    ///
+    /// let b: BFRDYN<8> = "AQID".into();
+    /// let mut out = [0u8; 3];
+    /// assert_eq!(3, b.decode_base64(&mut out).unwrap());
+    /// assert_eq!([1, 2, 3], out);
+    /// ```
+    ///
+    pub fn decode_base64(&self, out: &mut [u8]) -> Result<usize, err::InvalidFormat> {
+        self.decode_bytes(&BASE64, out)
+    }
+
+    /// decode padded Base32 into `out`, return the number of bytes written
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    ///
+    /// let b: BFRDYN<16> = "MZXW6YQ=".into();
+    /// let mut out = [0u8; 8];
+    /// let n = b.decode_base32(&mut out).unwrap();
+    /// assert_eq!(b"foob", &out[..n]);
+    /// ```
+    ///
+    pub fn decode_base32(&self, out: &mut [u8]) -> Result<usize, err::InvalidFormat> {
+        self.decode_bytes(&BASE32, out)
+    }
+}
@@ -0,0 +1,243 @@
+//! Edit distance and similarity between a [BFRDYN] and any text.
+//! Everything is computed over chars (never raw bytes) with scratch rows of
+//! CAPACITY entries on the stack, indexed by the chars of the buffer,
+//! so the other text can have any length.
+//! # example
+//! ```
+//! use cbfr::BFRDYN;
+//!
+//! let name: BFRDYN<32> = "kitten".into();
+//! assert_eq!(3, name.levenshtein("sitting"));
+//! assert_eq!(None, name.distance_within("sitting", 2));
+//! assert_eq!(5, name.lcs_len("knitting"));
+//! ```
+
+use crate::cb::BFRDYN;
+
+impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
+    /// chars of the buffer, at most CAPACITY of them
+    fn char_array(&self) -> ([char; CAPACITY], usize) {
+        let mut arr = ['\0'; CAPACITY];
+        let mut n = 0;
+        for c in self.as_str().chars() {
+            arr[n] = c;
+            n += 1;
+        }
+        (arr, n)
+    }
+
+    /// levenshtein distance that give up (None) as soon as it must exceed `k`
+    fn levenshtein_bounded(&self, other: &str, k: usize) -> Option<usize> {
+        let (a, n) = self.char_array();
+        let m = other.chars().count();
+        if n.abs_diff(m) > k { return None; }
+        // row[j] = distance between the chars of other seen so far and a[..=j],
+        // distance to the empty prefix of a is the number of chars seen
+        let mut row = [0usize; CAPACITY];
+        for (j, slot) in row[..n].iter_mut().enumerate() { *slot = j + 1; }
+        for (i, c) in other.chars().enumerate() {
+            let mut diag = i;
+            let mut left = i + 1;
+            let mut best = left;
+            for j in 0..n {
+                let up = row[j];
+                let v = (diag + (a[j] != c) as usize).min(up + 1).min(left + 1);
+                diag = up;
+                row[j] = v;
+                left = v;
+                best = best.min(v);
+            }
+            if best > k { return None; }
+        }
+        let d = if n == 0 { m } else { row[n - 1] };
+        (d <= k).then_some(d)
+    }
+
+    /// Levenshtein distance: number of char insertions, deletions and substitutions
+    /// needed to turn the buffer into `other`
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    ///
+    /// let b: BFRDYN<16> = "flaw".into();
+    /// assert_eq!(2, b.levenshtein("lawn"));
+    /// assert_eq!(1, BFRDYN::<16>::from("café").levenshtein("cafe"));
+    /// ```
+    ///
+    pub fn levenshtein(&self, other: impl AsRef<str>) -> usize {
+        self.levenshtein_bounded(other.as_ref(), usize::MAX).unwrap_or(usize::MAX)
+    }
+
+    /// Levenshtein distance if it is at most `k`, None otherwise.
+    /// Stop as soon as every entry of the current row exceed `k`,
+    /// cheaper than [levenshtein](Self::levenshtein) for threshold checks
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    ///
+    /// let b: BFRDYN<32> = "Jonathan".into();
+    /// assert_eq!(Some(1), b.distance_within("Jonathon", 2));
+    /// assert_eq!(None, b.distance_within("Jon", 2));
+    /// ```
+    ///
+    pub fn distance_within(&self, other: impl AsRef<str>, k: usize) -> Option<usize> {
+        self.levenshtein_bounded(other.as_ref(), k)
+    }
+
+    /// Damerau-Levenshtein distance (optimal string alignment variant):
+    /// like levenshtein but swapping 2 adjacent chars cost 1.
+    /// A substring is never edited twice, so `"ca"` to `"abc"` is 3, not 2
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    ///
+    /// let b: BFRDYN<16> = "recieve".into();
+    /// assert_eq!(1, b.damerau_levenshtein("receive"));
+    /// assert_eq!(2, b.levenshtein("receive"));
+    /// ```
+    ///
+    pub fn damerau_levenshtein(&self, other: impl AsRef<str>) -> usize {
+        let (a, n) = self.char_array();
+        // 3 rows: 2 chars of other ago, previous and current.
+        // Column 0 (empty prefix of a) is not stored, it equal the row number
+        let mut before = [0usize; CAPACITY];
+        let mut prev = [0usize; CAPACITY];
+        let mut cur = [0usize; CAPACITY];
+        for (j, slot) in prev[..n].iter_mut().enumerate() { *slot = j + 1; }
+        let mut last = None;
+        let mut m = 0;
+        for (i, c) in other.as_ref().chars().enumerate() {
+            for j in 0..n {
+                let diag = if j == 0 { i } else { prev[j - 1] };
+                let left = if j == 0 { i + 1 } else { cur[j - 1] };
+                let mut v = (diag + (a[j] != c) as usize).min(prev[j] + 1).min(left + 1);
+                if let Some(p) = last {
+                    if j > 0 && a[j] == p && a[j - 1] == c {
+                        let swap = if j == 1 { i - 1 } else { before[j - 2] };
+                        v = v.min(swap + 1);
+                    }
+                }
+                cur[j] = v;
+            }
+            std::mem::swap(&mut before, &mut prev);
+            std::mem::swap(&mut prev, &mut cur);
+            last = Some(c);
+            m = i + 1;
+        }
+        if n == 0 { m } else { prev[n - 1] }
+    }
+
+    /// Hamming distance: number of positions where the chars differ,
+    /// None if the 2 texts don't have the same number of chars
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    ///
+    /// let b: BFRDYN<16> = "karolin".into();
+    /// assert_eq!(Some(3), b.hamming("kathrin"));
+    /// assert_eq!(None, b.hamming("karol"));
+    /// ```
+    ///
+    pub fn hamming(&self, other: impl AsRef<str>) -> Option<usize> {
+        let mut a = self.as_str().chars();
+        let mut b = other.as_ref().chars();
+        let mut d = 0;
+        loop {
+            match (a.next(), b.next()) {
+                (Some(x), Some(y)) => d += (x != y) as usize,
+                (None, None) => return Some(d),
+                _ => return None,
+            }
+        }
+    }
+
+    /// Jaro similarity, from 0.0 (nothing in common) to 1.0 (same text)
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    ///
+    /// let b: BFRDYN<16> = "MARTHA".into();
+    /// assert!((b.jaro("MARHTA") - 0.944).abs() < 0.001);
+    /// ```
+    ///
+    pub fn jaro(&self, other: impl AsRef<str>) -> f64 {
+        let other = other.as_ref();
+        let (a, n) = self.char_array();
+        let m = other.chars().count();
+        if n == 0 && m == 0 { return 1.0; }
+        if n == 0 || m == 0 { return 0.0; }
+        let window = (n.max(m) / 2).saturating_sub(1);
+        let mut used = [false; CAPACITY];
+        // chars of other that found a partner, in the order of other
+        let mut matched = ['\0'; CAPACITY];
+        let mut count = 0;
+        for (i, c) in other.chars().enumerate() {
+            let lo = i.saturating_sub(window);
+            let hi = (i + window + 1).min(n);
+            if let Some(j) = (lo..hi).find(|&j| !used[j] && a[j] == c) {
+                used[j] = true;
+                matched[count] = c;
+                count += 1;
+            }
+        }
+        if count == 0 { return 0.0; }
+        let half_swaps = a[..n].iter().zip(used)
+            .filter(|(_, u)| *u)
+            .zip(&matched[..count])
+            .filter(|((x, _), y)| *x != *y)
+            .count();
+        let count = count as f64;
+        let t = (half_swaps / 2) as f64;
+        (count / n as f64 + count / m as f64 + (count - t) / count) / 3.0
+    }
+
+    /// Jaro-Winkler similarity: [jaro](Self::jaro) boosted by up to 4 common leading chars
+    /// (scaling factor 0.1) when the jaro similarity is above 0.7
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    ///
+    /// let b: BFRDYN<16> = "MARTHA".into();
+    /// assert!((b.jaro_winkler("MARHTA") - 0.961).abs() < 0.001);
+    /// assert_eq!(1.0, b.jaro_winkler("MARTHA"));
+    /// assert_eq!(0.0, b.jaro_winkler("xyz"));
+    /// ```
+    ///
+    pub fn jaro_winkler(&self, other: impl AsRef<str>) -> f64 {
+        let other = other.as_ref();
+        let sim = self.jaro(other);
+        if sim <= 0.7 { return sim; }
+        let prefix = self.as_str().chars().zip(other.chars())
+            .take(4)
+            .take_while(|(x, y)| x == y)
+            .count();
+        sim + 0.1 * prefix as f64 * (1.0 - sim)
+    }
+
+    /// length (in chars) of the longest common subsequence
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    ///
+    /// let b: BFRDYN<16> = "ABCBDAB".into();
+    /// assert_eq!(4, b.lcs_len("BDCABA"));
+    /// assert_eq!(0, b.lcs_len(""));
+    /// ```
+    ///
+    pub fn lcs_len(&self, other: impl AsRef<str>) -> usize {
+        let (a, n) = self.char_array();
+        let mut row = [0usize; CAPACITY];
+        for c in other.as_ref().chars() {
+            let mut diag = 0;
+            let mut left = 0;
+            for j in 0..n {
+                let up = row[j];
+                let v = if a[j] == c { diag + 1 } else { up.max(left) };
+                diag = up;
+                row[j] = v;
+                left = v;
+            }
+        }
+        if n == 0 { 0 } else { row[n - 1] }
+    }
+}
@@ -7,7 +7,8 @@ pub struct ErrorBase {
     buffer: usize,
     value: usize,
     len: usize,
-    index: usize
+    index: usize,
+    reason: &'static str
 }
 
 #[doc = "hidden"]
@@ -23,8 +24,8 @@ impl Display for NotEnoughCapacity {
     }
 }
 impl NotEnoughCapacity {
-    pub fn throw(buffer: usize, value: usize) -> Self {
-        Self(ErrorBase { buffer, value, ..Default::default() })
+    pub const fn throw(buffer: usize, value: usize) -> Self {
+        Self(ErrorBase { buffer, value, len: 0, index: 0, reason: "" })
     }
 }
 
@@ -46,6 +47,27 @@ impl InvalidIndex {
     }
 }
 
+#[doc = "hidden"]
+#[derive(Debug)]
+pub struct InvalidFormat(ErrorBase);
+impl Error for InvalidFormat {}
+impl Display for InvalidFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let err_msg = 
+            format!("{} at index {}", self.0.reason, self.0.index);
+        write!(f, "InvalidFormat: \"{}\"", err_msg)
+    }
+}
+impl InvalidFormat {
+    pub fn throw(index: usize, reason: &'static str) -> Self {
+        Self(ErrorBase { index, reason, ..Default::default() })
+    }
+    /// position of the malformed input
+    pub fn index(&self) -> usize { self.0.index }
+    /// short description of what is wrong
+    pub fn reason(&self) -> &'static str { self.0.reason }
+}
+
 impl From<InvalidIndex> for NotEnoughCapacity {
     fn from(value: InvalidIndex) -> Self {
         Self(ErrorBase { buffer: value.0.len, value: value.0.index, ..Default::default() })
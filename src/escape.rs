@@ -0,0 +1,377 @@
+//! Escape and unescape codecs for JSON strings, HTML text, C/Rust string literals
+//! and POSIX shell words. Output go to any `fmt::Write` sink, a [BFRDYN] included.
+//!
+//! Escaping can only fail when the sink is full (`fmt::Error`). Unescaping return
+//! InvalidFormat with the byte position of the malformed sequence,
+//! or of the input that didn't fit when the sink is full.
+//! # example
+//! ```
+//! use cbfr::BFRDYN;
+//!
+//! let msg: BFRDYN<32> = "say \"hi\"\n".into();
+//! let mut json: BFRDYN<32> = BFRDYN::new();
+//! msg.escape_json(&mut json).unwrap();
+//! assert_eq!(r#"say \"hi\"\n"#, json.as_str());
+//!
+//! let mut back: BFRDYN<32> = BFRDYN::new();
+//! json.unescape_json(&mut back).unwrap();
+//! assert_eq!(msg, back);
+//! ```
+
+use std::fmt::{self, Write};
+
+use crate::cb::BFRDYN;
+use crate::errors as err;
+
+/// named HTML entities understood by unescape_html
+const ENTITIES: [(&str, char); 24] = [
+    ("amp", '&'), ("lt", '<'), ("gt", '>'), ("quot", '"'), ("apos", '\''),
+    ("nbsp", '\u{a0}'), ("copy", '©'), ("reg", '®'), ("trade", '™'), ("hellip", '…'),
+    ("mdash", '—'), ("ndash", '–'), ("lsquo", '‘'), ("rsquo", '’'), ("ldquo", '“'),
+    ("rdquo", '”'), ("laquo", '«'), ("raquo", '»'), ("euro", '€'), ("middot", '·'),
+    ("deg", '°'), ("times", '×'), ("divide", '÷'), ("shy", '\u{ad}'),
+];
+
+/// write to the sink, a full sink is reported at input position `at`
+fn emit(out: &mut impl Write, at: usize, s: &str) -> Result<(), err::InvalidFormat> {
+    out.write_str(s).map_err(|_| err::InvalidFormat::throw(at, "output is full"))
+}
+
+fn emit_char(out: &mut impl Write, at: usize, c: char) -> Result<(), err::InvalidFormat> {
+    out.write_char(c).map_err(|_| err::InvalidFormat::throw(at, "output is full"))
+}
+
+/// value of `digits` hex digits at the start of `s`
+fn hex(s: &[u8], digits: usize) -> Option<u32> {
+    if s.len() < digits { return None; }
+    s[..digits].iter().try_fold(0u32, |acc, b| Some(acc * 16 + (*b as char).to_digit(16)?))
+}
+
+impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
+    /// escape the buffer as the content of a JSON string (without the quotes):
+    /// `"` `\` and control chars are escaped, everything else is kept
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    ///
+    /// let b: BFRDYN<32> = "tab\there \u{1}é".into();
+    /// let mut out = String::new();
+    /// b.escape_json(&mut out).unwrap();
+    /// assert_eq!(r"tab\there \u0001é", out);
+    /// ```
+    ///
+    pub fn escape_json(&self, out: &mut impl Write) -> fmt::Result {
+        for c in self.as_str().chars() {
+            match c {
+                '"' => out.write_str("\\\"")?,
+                '\\' => out.write_str("\\\\")?,
+                '\n' => out.write_str("\\n")?,
+                '\r' => out.write_str("\\r")?,
+                '\t' => out.write_str("\\t")?,
+                '\u{8}' => out.write_str("\\b")?,
+                '\u{c}' => out.write_str("\\f")?,
+                c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32)?,
+                c => out.write_char(c)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// decode the content of a JSON string (without the quotes),
+    /// `\uXXXX` surrogate pairs included
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    ///
+    /// let b: BFRDYN<32> = r"\u00e9t\u00e9 \ud83d\ude00".into();
+    /// let mut out: BFRDYN<32> = BFRDYN::new();
+    /// b.unescape_json(&mut out).unwrap();
+    /// assert_eq!("été 😀", out.as_str());
+    ///
+    /// let bad: BFRDYN<32> = r"ok \ud83d alone".into();
+    /// let e = bad.unescape_json(&mut String::new()).unwrap_err();
+    /// assert_eq!((3, "lone surrogate"), (e.index(), e.reason()));
+    /// ```
+    ///
+    pub fn unescape_json(&self, out: &mut impl Write) -> Result<(), err::InvalidFormat> {
+        let s = self.as_str();
+        let bytes = s.as_bytes();
+        let mut chars = s.char_indices();
+        while let Some((at, c)) = chars.next() {
+            if c != '\\' {
+                if (c as u32) < 0x20 {
+                    return Err(err::InvalidFormat::throw(at, "unescaped control character"));
+                }
+                emit_char(out, at, c)?;
+                continue;
+            }
+            let Some((_, e)) = chars.next() else {
+                return Err(err::InvalidFormat::throw(at, "unfinished escape"));
+            };
+            let decoded = match e {
+                '"' => '"',
+                '\\' => '\\',
+                '/' => '/',
+                'n' => '\n',
+                'r' => '\r',
+                't' => '\t',
+                'b' => '\u{8}',
+                'f' => '\u{c}',
+                'u' => {
+                    let invalid = || err::InvalidFormat::throw(at, "invalid unicode escape");
+                    let high = hex(&bytes[at + 2..], 4).ok_or_else(invalid)?;
+                    let mut code = high;
+                    let mut used = 4;
+                    if (0xD800..0xDC00).contains(&high) {
+                        let low = match &bytes[at + 6..] {
+                            [b'\\', b'u', rest @ ..] => hex(rest, 4),
+                            _ => None,
+                        };
+                        match low {
+                            Some(low @ 0xDC00..0xE000) => {
+                                code = 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00);
+                                used += 6;
+                            },
+                            _ => return Err(err::InvalidFormat::throw(at, "lone surrogate")),
+                        }
+                    }
+                    // hex digits are ascii, skip them as chars
+                    for _ in 0..used { chars.next(); }
+                    char::from_u32(code).ok_or_else(|| err::InvalidFormat::throw(at, "lone surrogate"))?
+                },
+                _ => return Err(err::InvalidFormat::throw(at, "invalid escape")),
+            };
+            emit_char(out, at, decoded)?;
+        }
+        Ok(())
+    }
+
+    /// escape `& < > " '` for HTML text and attribute values
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    ///
+    /// let b: BFRDYN<32> = r#"<a href="x">Tom & Jerry's</a>"#.into();
+    /// let mut out: BFRDYN<96> = BFRDYN::new();
+    /// b.escape_html(&mut out).unwrap();
+    /// assert_eq!("&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&#39;s&lt;/a&gt;", out.as_str());
+    /// ```
+    ///
+    pub fn escape_html(&self, out: &mut impl Write) -> fmt::Result {
+        for c in self.as_str().chars() {
+            match c {
+                '&' => out.write_str("&amp;")?,
+                '<' => out.write_str("&lt;")?,
+                '>' => out.write_str("&gt;")?,
+                '"' => out.write_str("&quot;")?,
+                '\'' => out.write_str("&#39;")?,
+                c => out.write_char(c)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// decode HTML entities: a few common named ones (`&amp;` `&lt;` `&nbsp;` `&copy;` `&euro;` ...)
+    /// and every numeric one (`&#233;` `&#xe9;`). A `&` not followed by a letter or `#` is kept as is
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    ///
+    /// let b: BFRDYN<64> = "caf&#233; &amp; cr&#xE8;me &euro;5 & more".into();
+    /// let mut out: BFRDYN<64> = BFRDYN::new();
+    /// b.unescape_html(&mut out).unwrap();
+    /// assert_eq!("café & crème €5 & more", out.as_str());
+    ///
+    /// let bad: BFRDYN<32> = "a &bogus; b".into();
+    /// assert_eq!(2, bad.unescape_html(&mut String::new()).unwrap_err().index());
+    /// ```
+    ///
+    pub fn unescape_html(&self, out: &mut impl Write) -> Result<(), err::InvalidFormat> {
+        let s = self.as_str();
+        let mut pos = 0;
+        while let Some(i) = s[pos..].find('&') {
+            let at = pos + i;
+            emit(out, pos, &s[pos..at])?;
+            let rest = &s[at + 1..];
+            if !rest.starts_with(|c: char| c == '#' || c.is_ascii_alphabetic()) {
+                emit(out, at, "&")?;
+                pos = at + 1;
+                continue;
+            }
+            let Some(end) = rest.find(';') else {
+                return Err(err::InvalidFormat::throw(at, "unterminated entity"));
+            };
+            let name = &rest[..end];
+            let decoded = if let Some(num) = name.strip_prefix('#') {
+                let code = match num.strip_prefix(['x', 'X']) {
+                    Some(h) => u32::from_str_radix(h, 16),
+                    None => num.parse::<u32>(),
+                };
+                code.ok().filter(|c| *c != 0 && !num.contains('+')).and_then(char::from_u32)
+                    .ok_or(err::InvalidFormat::throw(at, "invalid character reference"))?
+            } else {
+                ENTITIES.iter().find(|(n, _)| *n == name).map(|(_, c)| *c)
+                    .ok_or(err::InvalidFormat::throw(at, "unknown entity"))?
+            };
+            emit_char(out, at, decoded)?;
+            pos = at + 1 + end + 1;
+        }
+        emit(out, pos, &s[pos..])
+    }
+
+    /// escape the buffer as the content of a C or Rust string literal:
+    /// `\n \r \t \0 \\ \" \'` and `\xHH` for other control chars, non ASCII chars are kept.
+    /// In C a hex digit right after a `\xHH` would extend it, so that digit is escaped too
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    ///
+    /// let b: BFRDYN<32> = "a\"b\\\n\u{7f}".into();
+    /// let mut out = String::new();
+    /// b.escape_c(&mut out).unwrap();
+    /// assert_eq!(r#"a\"b\\\n\x7f"#, out);
+    ///
+    /// let b: BFRDYN<32> = "\u{1}a\u{0}1g".into();
+    /// let mut out = String::new();
+    /// b.escape_c(&mut out).unwrap();
+    /// assert_eq!(r"\x01\x61\x00\x31g", out);
+    /// let mut back = String::new();
+    /// BFRDYN::<32>::from(out.as_str()).unescape_c(&mut back).unwrap();
+    /// assert_eq!(b.as_str(), back);
+    /// ```
+    ///
+    pub fn escape_c(&self, out: &mut impl Write) -> fmt::Result {
+        let mut chars = self.as_str().chars().peekable();
+        // the last thing written is a \xHH escape
+        let mut after_hex = false;
+        while let Some(c) = chars.next() {
+            if after_hex && c.is_ascii_hexdigit() {
+                write!(out, "\\x{:02x}", c as u32)?;
+                continue;
+            }
+            after_hex = false;
+            match c {
+                '\n' => out.write_str("\\n")?,
+                '\r' => out.write_str("\\r")?,
+                '\t' => out.write_str("\\t")?,
+                // \0 followed by an octal digit would read back as one octal escape
+                '\0' if chars.peek().is_some_and(|n| ('0'..='7').contains(n)) => {
+                    out.write_str("\\x00")?;
+                    after_hex = true;
+                },
+                '\0' => out.write_str("\\0")?,
+                '\\' => out.write_str("\\\\")?,
+                '"' => out.write_str("\\\"")?,
+                '\'' => out.write_str("\\'")?,
+                c if c.is_ascii_control() => {
+                    write!(out, "\\x{:02x}", c as u32)?;
+                    after_hex = true;
+                },
+                c => out.write_char(c)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// decode a C or Rust string literal content: simple escapes (`\n \t \a \v \? ...`),
+    /// octal `\ooo`, `\xHH`, C `\uXXXX` / `\UXXXXXXXX` and Rust `\u{...}`.
+    /// Byte escapes must be ASCII since the output is UTF-8
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    ///
+    /// let b: BFRDYN<32> = r"tab\tnul\0 \101\x42 \u{e9}è".into();
+    /// let mut out = String::new();
+    /// b.unescape_c(&mut out).unwrap();
+    /// assert_eq!("tab\tnul\0 AB éè", out);
+    ///
+    /// let bad: BFRDYN<32> = r"ok\q".into();
+    /// assert_eq!((2, "invalid escape"), {
+    ///     let e = bad.unescape_c(&mut String::new()).unwrap_err();
+    ///     (e.index(), e.reason())
+    /// });
+    /// ```
+    ///
+    pub fn unescape_c(&self, out: &mut impl Write) -> Result<(), err::InvalidFormat> {
+        let s = self.as_str();
+        let bytes = s.as_bytes();
+        let mut pos = 0;
+        while let Some(i) = s[pos..].find('\\') {
+            let at = pos + i;
+            emit(out, pos, &s[pos..at])?;
+            let Some(&e) = bytes.get(at + 1) else {
+                return Err(err::InvalidFormat::throw(at, "unfinished escape"));
+            };
+            let invalid = |reason| err::InvalidFormat::throw(at, reason);
+            // decoded char and length of the escape
+            let (code, len) = match e {
+                b'n' => ('\n' as u32, 2),
+                b'r' => ('\r' as u32, 2),
+                b't' => ('\t' as u32, 2),
+                b'a' => (0x07, 2),
+                b'b' => (0x08, 2),
+                b'f' => (0x0c, 2),
+                b'v' => (0x0b, 2),
+                b'\\' | b'"' | b'\'' | b'?' => (e as u32, 2),
+                b'0'..=b'7' => {
+                    let digits = bytes[at + 1..].iter().take(3).take_while(|b| matches!(b, b'0'..=b'7')).count();
+                    let value = bytes[at + 1..at + 1 + digits].iter().fold(0, |acc, b| acc * 8 + (b - b'0') as u32);
+                    (value, 1 + digits)
+                },
+                b'x' => {
+                    let digits = bytes[at + 2..].iter().take(2).take_while(|b| b.is_ascii_hexdigit()).count();
+                    if digits == 0 { return Err(invalid("invalid hex escape")); }
+                    (hex(&bytes[at + 2..], digits).unwrap_or(0), 2 + digits)
+                },
+                b'u' if bytes.get(at + 2) == Some(&b'{') => {
+                    let close = s[at + 3..].find('}').ok_or(invalid("invalid unicode escape"))?;
+                    let digits = &s[at + 3..at + 3 + close];
+                    if digits.is_empty() || digits.len() > 6 || digits.starts_with('+') {
+                        return Err(invalid("invalid unicode escape"));
+                    }
+                    let code = u32::from_str_radix(digits, 16).map_err(|_| invalid("invalid unicode escape"))?;
+                    (code, 4 + close)
+                },
+                b'u' => (hex(&bytes[at + 2..], 4).ok_or(invalid("invalid unicode escape"))?, 6),
+                b'U' => (hex(&bytes[at + 2..], 8).ok_or(invalid("invalid unicode escape"))?, 10),
+                _ => return Err(invalid("invalid escape")),
+            };
+            if matches!(e, b'0'..=b'7' | b'x') && code >= 0x80 {
+                return Err(invalid("non ASCII byte escape"));
+            }
+            let c = char::from_u32(code).ok_or(invalid("invalid unicode escape"))?;
+            emit_char(out, at, c)?;
+            pos = at + len;
+        }
+        emit(out, pos, &s[pos..])
+    }
+
+    /// quote the buffer as one POSIX shell word: kept as is when it only contains
+    /// safe chars (letters, digits and `_ @ % + = : , . / -`), single quoted otherwise
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    ///
+    /// let mut out = String::new();
+    /// BFRDYN::<32>::from("src/main.rs").shell_quote(&mut out).unwrap();
+    /// out.push(' ');
+    /// BFRDYN::<32>::from("it's $HOME").shell_quote(&mut out).unwrap();
+    /// out.push(' ');
+    /// BFRDYN::<32>::new().shell_quote(&mut out).unwrap();
+    /// assert_eq!(r"src/main.rs 'it'\''s $HOME' ''", out);
+    /// ```
+    ///
+    pub fn shell_quote(&self, out: &mut impl Write) -> fmt::Result {
+        let s = self.as_str();
+        let safe = |b: u8| b.is_ascii_alphanumeric() || b"_@%+=:,./-".contains(&b);
+        if !s.is_empty() && s.bytes().all(safe) {
+            return out.write_str(s);
+        }
+        out.write_char('\'')?;
+        for (i, part) in s.split('\'').enumerate() {
+            if i > 0 { out.write_str("'\\''")?; }
+            out.write_str(part)?;
+        }
+        out.write_char('\'')
+    }
+}
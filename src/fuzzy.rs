@@ -0,0 +1,232 @@
+//! Fuzzy finder scoring (fzf v1 style): the query must appear in the candidate as a
+//! subsequence, matches at word boundaries, camelCase humps and consecutive matches
+//! score higher, gaps cost a little. The search is linear and never allocate:
+//! a forward pass find the earliest end of the subsequence, a backward pass from there
+//! find the shortest window, then that window is scored.
+//!
+//! Matching is smart case: case insensitive unless the query contains an uppercase char.
+//! # example
+//! ```
+//! use cbfr::BFRDYN;
+//! use cbfr::fuzzy::{self, TopK};
+//!
+//! let cmds: [BFRDYN<32>; 4] = ["git commit", "go to file", "format document", "git checkout"]
+//!     .map(BFRDYN::from);
+//!
+//! let score = cmds[1].fuzzy_score("gtf").unwrap();
+//! assert_eq!(&[0, 3, 6], score.positions());   // byte offsets to highlight
+//!
+//! let top: TopK<2> = fuzzy::top_k(&cmds, "gc");
+//! let names: Vec<&str> = top.iter().map(|(i, _)| cmds[*i].as_str()).collect();
+//! assert_eq!(vec!["git commit", "git checkout"], names);
+//! ```
+
+use crate::cb::BFRDYN;
+
+/// score of each matched char
+pub const SCORE_MATCH: i32 = 16;
+/// penalty for the first char of a gap
+pub const GAP_START: i32 = -3;
+/// penalty for every other char of a gap
+pub const GAP_EXTENSION: i32 = -1;
+/// bonus for a match at the start of a word
+pub const BONUS_BOUNDARY: i32 = SCORE_MATCH / 2;
+/// bonus for a camelCase hump or a letter to digit transition
+pub const BONUS_CAMEL: i32 = BONUS_BOUNDARY + GAP_EXTENSION;
+/// minimal bonus for a match right after the previous one
+pub const BONUS_CONSECUTIVE: i32 = -(GAP_START + GAP_EXTENSION);
+
+#[derive(Clone, Copy, PartialEq)]
+enum Class { NonWord, Lower, Upper, Digit }
+
+fn class(c: char) -> Class {
+    if c.is_uppercase() { Class::Upper }
+    else if c.is_numeric() { Class::Digit }
+    else if c.is_alphabetic() { Class::Lower }
+    else { Class::NonWord }
+}
+
+/// bonus of a char given the class of the char before it
+fn bonus(prev: Class, cur: Class) -> i32 {
+    match (prev, cur) {
+        (_, Class::NonWord) => 0,
+        (Class::NonWord, _) => BONUS_BOUNDARY,
+        (Class::Lower, Class::Upper) => BONUS_CAMEL,
+        (Class::Lower | Class::Upper, Class::Digit) => BONUS_CAMEL,
+        _ => 0,
+    }
+}
+
+/// lowercase a char if the match is case insensitive
+fn fold(c: char, icase: bool) -> char {
+    if !icase { c }
+    else if c.is_ascii() { c.to_ascii_lowercase() }
+    else { c.to_lowercase().next().unwrap_or(c) }
+}
+
+/// Score of a fuzzy match and the byte offset of each matched char,
+/// Q is the maximum number of query chars
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Score<const Q: usize = 32> {
+    value: i32,
+    positions: [usize; Q],
+    len: usize,
+}
+
+impl<const Q: usize> Score<Q> {
+    /// score `query` against `text`, None if the query is not a subsequence of the text
+    /// or has more than Q chars. An empty query match everything with a score of 0
+    /// # example
+    /// ```
+    /// use cbfr::fuzzy::Score;
+    ///
+    /// let word: Score = Score::new("foo_bar", "fb").unwrap();
+    /// let camel: Score = Score::new("fooBar", "fb").unwrap();
+    /// let inner: Score = Score::new("foobar", "fb").unwrap();
+    /// assert_eq!(&[0, 4], word.positions());
+    /// assert!(word.value() > inner.value() && camel.value() > inner.value());
+    /// assert!(Score::<32>::new("foobar", "fB").is_none());   // uppercase query is case sensitive
+    /// ```
+    ///
+    pub fn new(text: &str, query: &str) -> Option<Self> {
+        let mut score = Self { value: 0, positions: [0; Q], len: 0 };
+        if query.chars().count() > Q { return None; }
+        let icase = !query.chars().any(char::is_uppercase);
+        let same = |c: char, q: char| fold(c, icase) == q;
+
+        // forward: earliest end of the subsequence
+        let mut want = query.chars();
+        let Some(mut q) = want.next() else { return Some(score) };
+        let mut end = None;
+        for (i, c) in text.char_indices() {
+            if !same(c, q) { continue; }
+            match want.next() {
+                Some(next) => q = next,
+                None => {
+                    end = Some(i + c.len_utf8());
+                    break;
+                },
+            }
+        }
+        let end = end?;
+
+        // backward: latest start of the subsequence ending there
+        let mut want = query.chars().rev();
+        let mut q = want.next()?;
+        let mut start = 0;
+        for (i, c) in text[..end].char_indices().rev() {
+            if !same(c, q) { continue; }
+            match want.next() {
+                Some(next) => q = next,
+                None => {
+                    start = i;
+                    break;
+                },
+            }
+        }
+
+        // score the window
+        let mut want = query.chars().peekable();
+        let mut prev = text[..start].chars().next_back().map_or(Class::NonWord, class);
+        let mut in_gap = false;
+        let mut run = 0;
+        let mut chunk_bonus = 0;
+        for (i, c) in text[start..end].char_indices() {
+            let cur = class(c);
+            if want.next_if(|q| same(c, *q)).is_some() {
+                let mut b = bonus(prev, cur);
+                if run == 0 {
+                    chunk_bonus = b;
+                } else {
+                    if b >= BONUS_BOUNDARY { chunk_bonus = b; }
+                    b = b.max(chunk_bonus).max(BONUS_CONSECUTIVE);
+                }
+                score.value += SCORE_MATCH + if score.len == 0 { 2 * b } else { b };
+                score.positions[score.len] = start + i;
+                score.len += 1;
+                run += 1;
+                in_gap = false;
+            } else {
+                score.value += if in_gap { GAP_EXTENSION } else { GAP_START };
+                in_gap = true;
+                run = 0;
+            }
+            prev = cur;
+        }
+        Some(score)
+    }
+
+    /// the score, higher is better
+    pub const fn value(&self) -> i32 { self.value }
+
+    /// byte offset of each matched char in the candidate
+    pub fn positions(&self) -> &[usize] { &self.positions[..self.len] }
+}
+
+/// The K best candidates of a [top_k] ranking, best first
+#[derive(Debug, Clone)]
+pub struct TopK<const K: usize, const Q: usize = 32> {
+    items: [(usize, Score<Q>); K],
+    len: usize,
+}
+
+impl<const K: usize, const Q: usize> TopK<K, Q> {
+    /// number of ranked candidates
+    pub const fn len(&self) -> usize { self.len }
+
+    /// no candidate matched
+    pub const fn is_empty(&self) -> bool { self.len == 0 }
+
+    /// (index in the candidates slice, score) pairs, best first
+    pub fn as_slice(&self) -> &[(usize, Score<Q>)] { &self.items[..self.len] }
+
+    /// iterate (index in the candidates slice, score) pairs, best first
+    pub fn iter(&self) -> std::slice::Iter<'_, (usize, Score<Q>)> { self.as_slice().iter() }
+}
+
+/// rank candidates against a query and keep the K best, without heap allocation.
+/// Ties go to the shorter candidate, then to the first one in the slice
+/// # example
+/// ```
+/// use cbfr::BFRDYN;
+/// use cbfr::fuzzy::{self, TopK};
+///
+/// let files: [BFRDYN<32>; 4] = ["src/main.rs", "README.md", "src/map.rs", "Cargo.toml"]
+///     .map(BFRDYN::from);
+/// let top: TopK<3> = fuzzy::top_k(&files, "mrs");
+/// assert_eq!(2, top.len());
+/// assert_eq!(2, top.as_slice()[0].0);    // src/map.rs, shorter gap than src/main.rs
+/// ```
+///
+pub fn top_k<const K: usize, const Q: usize, const N: usize>(candidates: &[BFRDYN<N>], query: &str) -> TopK<K, Q> {
+    let empty = (0, Score { value: 0, positions: [0; Q], len: 0 });
+    let mut top = TopK { items: [empty; K], len: 0 };
+    for (index, candidate) in candidates.iter().enumerate() {
+        let Some(score) = Score::<Q>::new(candidate.as_str(), query) else { continue };
+        let better = |(other, s): &(usize, Score<Q>)| {
+            (score.value, std::cmp::Reverse(candidate.len())) > (s.value, std::cmp::Reverse(candidates[*other].len()))
+        };
+        let at = top.items[..top.len].iter().position(better).unwrap_or(top.len);
+        if at == K { continue; }
+        if top.len < K { top.len += 1; }
+        top.items[at..top.len].rotate_right(1);
+        top.items[at] = (index, score);
+    }
+    top
+}
+
+impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
+    /// fuzzy match a query (up to 32 chars) against the buffer, see [fuzzy](crate::fuzzy)
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    ///
+    /// let b: BFRDYN<32> = "open_recent_file".into();
+    /// assert!(b.fuzzy_score("orf").unwrap().value() > b.fuzzy_score("ecn").unwrap().value());
+    /// assert!(b.fuzzy_score("xyz").is_none());
+    /// ```
+    ///
+    pub fn fuzzy_score(&self, query: &str) -> Option<Score> {
+        Score::new(self.as_str(), query)
+    }
+}
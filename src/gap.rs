@@ -0,0 +1,371 @@
+use std::fmt::Display;
+
+use crate::cb::{BFRDYN, DEFCAPACITY, NecResult, IidxResult};
+use crate::errors as err;
+
+/// BFRGAP is a gap buffer for cursor based text editing.
+/// Text before the cursor is stored at the start of the internal array,
+/// text after the cursor is stored at the end, and the free space (the gap)
+/// sits in between. Inserting or deleting at the cursor is O(1) per char,
+/// moving the cursor costs O(distance) instead of shifting the whole buffer
+/// like BFRDYN::insert_ch does.
+/// The cursor always lies on a char boundary, so both halves stay valid utf-8.
+/// # example
+/// ```
+/// use cbfr::gap::BFRGAP;
+///
+/// let mut g: BFRGAP<64> = "Hello world".into();
+/// g.move_cursor(5).unwrap();
+/// g.insert_at_cursor(",").unwrap();
+/// g.move_word_right();
+/// g.insert_ch('!').unwrap();
+///
+/// assert_eq!("Hello, world!", g.to_string());
+/// ```
+#[derive(Debug, Clone)]
+pub struct BFRGAP<const CAPACITY: usize = DEFCAPACITY> {
+    arr: [u8; CAPACITY],
+    gap_start: usize,
+    gap_end: usize,
+}
+
+/// Create gap buffer instance from &str, the cursor is placed at the end
+/// # Example
+/// ```
+/// use cbfr::gap::BFRGAP;
+///
+/// let g: BFRGAP<16> = "some string".into();
+/// assert_eq!(11, g.cursor());
+/// ```
+/// # Panic
+/// Panic if "some string" len > 16
+///
+impl<const CAPACITY: usize> From<&str> for BFRGAP<CAPACITY> {
+    fn from(value: &str) -> Self {
+        let mut me = Self::new();
+        assert!(value.len() <= CAPACITY, "Not enough capacity!");
+        me.arr[..value.len()].copy_from_slice(value.as_bytes());
+        me.gap_start = value.len();
+        me
+    }
+}
+
+impl<const CAPACITY: usize> Display for BFRGAP<CAPACITY> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (left, right) = self.as_strs();
+        write!(f, "{}{}", left, right)
+    }
+}
+
+impl<const CAPACITY: usize> Default for BFRGAP<CAPACITY> {
+    fn default() -> Self { Self::new() }
+}
+
+/// char that belongs to a word for word wise movement and deletion
+fn is_word(c: char) -> bool { c.is_alphanumeric() || c == '_' }
+
+impl<const CAPACITY: usize> BFRGAP<CAPACITY> {
+    /// create new empty gap buffer
+    /// # example
+    /// ```
+    /// use cbfr::gap::BFRGAP;
+    /// let g = BFRGAP::<64>::new();
+    /// assert_eq!(0, g.len());
+    /// assert_eq!(64, g.capacity());
+    /// ```
+    ///
+    pub const fn new() -> Self {
+        Self { arr: [0u8; CAPACITY], gap_start: 0, gap_end: CAPACITY }
+    }
+
+    /// get buffer capacity
+    pub const fn capacity(&self) -> usize { CAPACITY }
+
+    /// get text len in bytes
+    pub const fn len(&self) -> usize { CAPACITY - (self.gap_end - self.gap_start) }
+
+    /// check if buffer is empty
+    pub const fn is_empty(&self) -> bool { self.len() == 0 }
+
+    /// get cursor position as byte offset of the text
+    pub const fn cursor(&self) -> usize { self.gap_start }
+
+    /// return text before and after the cursor
+    /// # example
+    /// ```
+    /// use cbfr::gap::BFRGAP;
+    ///
+    /// let mut g: BFRGAP<64> = "left right".into();
+    /// g.move_cursor(4).unwrap();
+    /// assert_eq!(("left", " right"), g.as_strs());
+    /// ```
+    ///
+    pub fn as_strs(&self) -> (&str, &str) {
+        // safety: only &str are copied in and the gap always
+        // moves by whole chars, so both halves are valid utf-8
+        unsafe {
+            (std::str::from_utf8_unchecked(&self.arr[..self.gap_start]),
+             std::str::from_utf8_unchecked(&self.arr[self.gap_end..]))
+        }
+    }
+
+    /// copy the text into a BFRDYN for rendering,
+    /// this takes only two copies (one for each side of the gap)
+    /// # example
+    /// ```
+    /// use cbfr::gap::BFRGAP;
+    /// use cbfr::BFRDYN;
+    ///
+    /// let mut g: BFRGAP<64> = "abc".into();
+    /// g.move_cursor(1).unwrap();
+    /// let b: BFRDYN<8> = g.to_bfrdyn().unwrap();
+    ///
+    /// assert_eq!("abc", b.as_str());
+    /// assert!(g.to_bfrdyn::<2>().is_err());
+    /// ```
+    ///
+    pub fn to_bfrdyn<const N: usize>(&self) -> Result<BFRDYN<N>, err::NotEnoughCapacity> {
+        let (left, right) = self.as_strs();
+        let mut b = BFRDYN::<N>::new();
+        if self.len() > N {
+            return Err(err::NotEnoughCapacity::throw(N, self.len()));
+        }
+        b.append_str(left)?;
+        b.append_str(right)?;
+        Ok(b)
+    }
+
+    /// move the cursor to byte offset `pos`.
+    /// Return InvalidIndex if `pos` is larger than len or not on a char boundary
+    /// # example
+    /// ```
+    /// use cbfr::gap::BFRGAP;
+    ///
+    /// let mut g: BFRGAP<64> = "añb".into();
+    /// assert!(g.move_cursor(1).is_ok());
+    /// assert!(g.move_cursor(2).is_err());   // inside 'ñ'
+    /// assert!(g.move_cursor(9).is_err());
+    /// assert_eq!(1, g.cursor());
+    /// ```
+    ///
+    pub fn move_cursor(&mut self, pos: usize) -> IidxResult {
+        let (left, right) = self.as_strs();
+        let valid = if pos <= self.gap_start {
+            left.is_char_boundary(pos)
+        } else {
+            pos <= self.len() && right.is_char_boundary(pos - self.gap_start)
+        };
+        if !valid {
+            return Err(err::InvalidIndex::throw(self.len(), pos));
+        }
+        if pos < self.gap_start {
+            let n = self.gap_start - pos;
+            self.arr.copy_within(pos..self.gap_start, self.gap_end - n);
+            self.gap_start = pos;
+            self.gap_end -= n;
+        } else {
+            let n = pos - self.gap_start;
+            self.arr.copy_within(self.gap_end..self.gap_end + n, self.gap_start);
+            self.gap_start += n;
+            self.gap_end += n;
+        }
+        Ok(())
+    }
+
+    /// move cursor one char to the left, return false if already at the start
+    pub fn move_left(&mut self) -> bool {
+        match self.as_strs().0.chars().next_back() {
+            Some(c) => self.move_cursor(self.gap_start - c.len_utf8()).is_ok(),
+            None => false,
+        }
+    }
+
+    /// move cursor one char to the right, return false if already at the end
+    /// # example
+    /// ```
+    /// use cbfr::gap::BFRGAP;
+    ///
+    /// let mut g: BFRGAP<64> = "é!".into();
+    /// g.move_cursor(0).unwrap();
+    /// assert!(g.move_right());
+    /// assert_eq!(2, g.cursor());
+    /// assert!(g.move_left());
+    /// assert!(!g.move_left());
+    /// ```
+    ///
+    pub fn move_right(&mut self) -> bool {
+        match self.as_strs().1.chars().next() {
+            Some(c) => self.move_cursor(self.gap_start + c.len_utf8()).is_ok(),
+            None => false,
+        }
+    }
+
+    /// byte offset of the start of the word before the cursor
+    fn word_start(&self) -> usize {
+        let left = self.as_strs().0;
+        let mut pos = left.len();
+        let mut chars = left.char_indices().rev().peekable();
+        while let Some(&(i, c)) = chars.peek() {
+            if is_word(c) { break; }
+            pos = i;
+            chars.next();
+        }
+        for (i, c) in chars {
+            if !is_word(c) { break; }
+            pos = i;
+        }
+        pos
+    }
+
+    /// byte offset of the end of the word after the cursor
+    fn word_end(&self) -> usize {
+        let right = self.as_strs().1;
+        let mut pos = 0;
+        let mut chars = right.chars().peekable();
+        while let Some(&c) = chars.peek() {
+            if is_word(c) { break; }
+            pos += c.len_utf8();
+            chars.next();
+        }
+        for c in chars {
+            if !is_word(c) { break; }
+            pos += c.len_utf8();
+        }
+        self.gap_start + pos
+    }
+
+    /// move cursor to the start of the previous word.
+    /// Word is a run of alphanumeric chars or '_'
+    /// # example
+    /// ```
+    /// use cbfr::gap::BFRGAP;
+    ///
+    /// let mut g: BFRGAP<64> = "one two, three".into();
+    /// g.move_word_left();
+    /// assert_eq!(9, g.cursor());
+    /// g.move_word_left();
+    /// assert_eq!(4, g.cursor());
+    /// ```
+    ///
+    pub fn move_word_left(&mut self) {
+        let pos = self.word_start();
+        self.move_cursor(pos).ok();
+    }
+
+    /// move cursor to the end of the next word
+    /// # example
+    /// ```
+    /// use cbfr::gap::BFRGAP;
+    ///
+    /// let mut g: BFRGAP<64> = "one two, three".into();
+    /// g.move_cursor(0).unwrap();
+    /// g.move_word_right();
+    /// assert_eq!(3, g.cursor());
+    /// g.move_word_right();
+    /// assert_eq!(7, g.cursor());
+    /// ```
+    ///
+    pub fn move_word_right(&mut self) {
+        let pos = self.word_end();
+        self.move_cursor(pos).ok();
+    }
+
+    /// insert &str at the cursor, the cursor is placed after the inserted text
+    /// # example
+    /// ```
+    /// use cbfr::gap::BFRGAP;
+    ///
+    /// let mut g: BFRGAP<8> = "ad".into();
+    /// g.move_cursor(1).unwrap();
+    /// g.insert_at_cursor("bc").unwrap();
+    ///
+    /// assert_eq!("abcd", g.to_string());
+    /// assert_eq!(3, g.cursor());
+    /// assert!(g.insert_at_cursor("too long").is_err());
+    /// ```
+    ///
+    pub fn insert_at_cursor(&mut self, text: &str) -> NecResult {
+        let total_len = self.len() + text.len();
+        if total_len > CAPACITY {
+            return Err(err::NotEnoughCapacity::throw(CAPACITY, total_len));
+        }
+        self.arr[self.gap_start..self.gap_start + text.len()].copy_from_slice(text.as_bytes());
+        self.gap_start += text.len();
+        Ok(())
+    }
+
+    /// insert a char at the cursor
+    /// # example
+    /// ```
+    /// use cbfr::gap::BFRGAP;
+    ///
+    /// let mut g: BFRGAP<8> = "nio".into();
+    /// g.move_cursor(1).unwrap();
+    /// g.insert_ch('ñ').unwrap();
+    /// assert_eq!("nñio", g.to_string());
+    /// ```
+    ///
+    pub fn insert_ch(&mut self, c: char) -> NecResult {
+        self.insert_at_cursor(c.encode_utf8(&mut [0u8; 4]))
+    }
+
+    /// delete the char before the cursor (backspace)
+    /// # example
+    /// ```
+    /// use cbfr::gap::BFRGAP;
+    ///
+    /// let mut g: BFRGAP<8> = "añ".into();
+    /// assert_eq!(Some('ñ'), g.delete_backward());
+    /// assert_eq!(Some('a'), g.delete_backward());
+    /// assert_eq!(None, g.delete_backward());
+    /// ```
+    ///
+    pub fn delete_backward(&mut self) -> Option<char> {
+        let c = self.as_strs().0.chars().next_back()?;
+        self.gap_start -= c.len_utf8();
+        Some(c)
+    }
+
+    /// delete the char after the cursor (delete)
+    /// # example
+    /// ```
+    /// use cbfr::gap::BFRGAP;
+    ///
+    /// let mut g: BFRGAP<8> = "añ".into();
+    /// g.move_cursor(0).unwrap();
+    /// assert_eq!(Some('a'), g.delete_forward());
+    /// assert_eq!(Some('ñ'), g.delete_forward());
+    /// assert_eq!(None, g.delete_forward());
+    /// ```
+    ///
+    pub fn delete_forward(&mut self) -> Option<char> {
+        let c = self.as_strs().1.chars().next()?;
+        self.gap_end += c.len_utf8();
+        Some(c)
+    }
+
+    /// delete the word before the cursor (with the non word chars
+    /// between it and the cursor) and return the number of bytes deleted
+    /// # example
+    /// ```
+    /// use cbfr::gap::BFRGAP;
+    ///
+    /// let mut g: BFRGAP<64> = "git commit --amend".into();
+    /// assert_eq!(5, g.delete_word());
+    /// assert_eq!("git commit --", g.to_string());
+    /// assert_eq!(9, g.delete_word());
+    /// assert_eq!("git ", g.to_string());
+    /// ```
+    ///
+    pub fn delete_word(&mut self) -> usize {
+        let n = self.gap_start - self.word_start();
+        self.gap_start -= n;
+        n
+    }
+
+    /// remove all text and move the cursor to the start
+    pub fn clear(&mut self) {
+        self.gap_start = 0;
+        self.gap_end = CAPACITY;
+    }
+}
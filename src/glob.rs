@@ -0,0 +1,219 @@
+use crate::cb::BFRDYN;
+use crate::errors as err;
+
+/// character that escape the next one in a pattern
+pub const ESCAPE: char = '\\';
+
+/// one element of a compiled pattern
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Token {
+    Char(char),
+    /// `?`, any char but '/'
+    Any,
+    /// `[...]`, char in (or not in) ranges[start..start + len], never '/'
+    Class { start: usize, len: usize, negated: bool },
+    /// `*`, any run of chars without '/'
+    Star,
+    /// `**`, any run of chars. When written `**/` the following '/'
+    /// is optional too so `a/**/b` match `a/b`
+    GlobStar { skip_slash: bool },
+    /// end of pattern
+    End,
+}
+
+/// Glob is a precompiled shell style wildcard pattern:
+/// - `?` match one char, `*` match any run of chars, both stop at '/'
+/// - `**` match across '/', `a/**/b` match `a/b`, `a/x/b`, `a/x/y/b` ...
+/// - `[abc]`, `[a-z]` match one char from the class, `[!a-z]` or `[^a-z]` negate it
+/// - `\` escape the next char
+///
+/// Matching simulate every position of the pattern at once, so it take
+/// O(text × pattern) time in the worst case, linear in the text for a given pattern,
+/// with no backtracking. TOKENS bound the compiled pattern and its class ranges.
+/// # example
+/// ```
+/// use cbfr::glob::Glob;
+///
+/// let src: Glob = Glob::new("src/**/*.rs").unwrap();
+/// assert!(src.is_match("src/main.rs"));
+/// assert!(src.is_match("src/a/b/lib.rs"));
+/// assert!(!src.is_match("src/a/b/lib.rs.bak"));
+///
+/// let log = Glob::<16>::new("app-[0-9]?.LOG").unwrap().case_insensitive(true);
+/// assert!(log.is_match("App-12.log"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Glob<const TOKENS: usize = 64> {
+    tokens: [Token; TOKENS],
+    len: usize,
+    ranges: [(char, char); TOKENS],
+    nranges: usize,
+    icase: bool,
+}
+
+/// compare 2 chars, ignoring case if asked
+fn same(a: char, b: char, icase: bool) -> bool {
+    a == b || (icase && a.to_lowercase().eq(b.to_lowercase()))
+}
+
+impl<const TOKENS: usize> Glob<TOKENS> {
+    /// compile a pattern. Return InvalidFormat with the position of the problem
+    /// for an unclosed class, a trailing escape or a pattern too long for TOKENS
+    /// # example
+    /// ```
+    /// use cbfr::glob::Glob;
+    ///
+    /// assert_eq!("unclosed class", Glob::<64>::new("[a-").unwrap_err().reason());
+    /// assert!(Glob::<4>::new("abcd").is_err());   // 4 chars + end
+    /// ```
+    ///
+    pub fn new(pattern: &str) -> Result<Self, err::InvalidFormat> {
+        let mut glob = Self {
+            tokens: [Token::End; TOKENS],
+            len: 0,
+            ranges: [('\0', '\0'); TOKENS],
+            nranges: 0,
+            icase: false,
+        };
+        let full = |at| err::InvalidFormat::throw(at, "pattern too large for glob capacity");
+        let mut chars = pattern.char_indices().peekable();
+        while let Some((at, c)) = chars.next() {
+            let token = match c {
+                '?' => Token::Any,
+                '*' if chars.peek().map(|p| p.1) == Some('*') => {
+                    chars.next();
+                    let after = pattern[at + 2..].chars().next();
+                    let starts_segment = at == 0 || pattern[..at].ends_with('/');
+                    Token::GlobStar { skip_slash: starts_segment && after == Some('/') }
+                },
+                '*' => Token::Star,
+                '[' => {
+                    let start = glob.nranges;
+                    let negated = matches!(chars.peek(), Some((_, '!' | '^')));
+                    if negated { chars.next(); }
+                    let mut first = true;
+                    loop {
+                        let lo = match chars.next() {
+                            None => return Err(err::InvalidFormat::throw(at, "unclosed class")),
+                            Some((_, ']')) if !first => break,
+                            Some((i, ESCAPE)) => chars.next()
+                                .ok_or(err::InvalidFormat::throw(i, "unfinished escape"))?.1,
+                            Some((_, c)) => c,
+                        };
+                        first = false;
+                        let mut hi = lo;
+                        let mut ahead = chars.clone();
+                        if let (Some((_, '-')), Some((_, h))) = (ahead.next(), ahead.next()) {
+                            if h != ']' {
+                                chars.next();
+                                chars.next();
+                                hi = h;
+                            }
+                        }
+                        if glob.nranges == TOKENS { return Err(full(at)); }
+                        glob.ranges[glob.nranges] = (lo.min(hi), lo.max(hi));
+                        glob.nranges += 1;
+                    }
+                    Token::Class { start, len: glob.nranges - start, negated }
+                },
+                ESCAPE => match chars.next() {
+                    Some((_, e)) => Token::Char(e),
+                    None => return Err(err::InvalidFormat::throw(at, "unfinished escape")),
+                },
+                c => Token::Char(c),
+            };
+            // keep one slot for End
+            if glob.len + 1 >= TOKENS { return Err(full(at)); }
+            glob.tokens[glob.len] = token;
+            glob.len += 1;
+        }
+        if TOKENS == 0 { return Err(full(0)); }
+        glob.tokens[glob.len] = Token::End;
+        Ok(glob)
+    }
+
+    /// enable or disable case insensitive matching
+    pub const fn case_insensitive(mut self, yes: bool) -> Self {
+        self.icase = yes;
+        self
+    }
+
+    fn class_match(&self, start: usize, len: usize, c: char) -> bool {
+        self.ranges[start..start + len].iter().any(|(lo, hi)| {
+            let inside = |c: char| (*lo..=*hi).contains(&c);
+            inside(c) || (self.icase && c.to_lowercase().chain(c.to_uppercase()).any(inside))
+        })
+    }
+
+    /// activate a position and every position reachable without consuming a char
+    fn enter(&self, state: &mut [bool; TOKENS], mut i: usize) {
+        loop {
+            // an empty `**/` also skip its '/'
+            if let Token::GlobStar { skip_slash: true } = self.tokens[i] {
+                self.enter(state, i + 2);
+            }
+            if state[i] { return; }
+            state[i] = true;
+            match self.tokens[i] {
+                Token::Star | Token::GlobStar { .. } => i += 1,
+                _ => return,
+            }
+        }
+    }
+
+    /// keep a star active after it consumed a char, the '/' of `**/` is required from now
+    fn stay(&self, state: &mut [bool; TOKENS], i: usize) {
+        if !state[i] {
+            state[i] = true;
+            self.enter(state, i + 1);
+        }
+    }
+
+    /// check if the whole text match the pattern
+    pub fn is_match(&self, text: impl AsRef<str>) -> bool {
+        if TOKENS == 0 { return false; }
+        let mut cur = [false; TOKENS];
+        let mut next = [false; TOKENS];
+        self.enter(&mut cur, 0);
+        for c in text.as_ref().chars() {
+            let mut alive = false;
+            for (i, _) in cur[..self.len].iter().enumerate().filter(|(_, on)| **on) {
+                alive |= match self.tokens[i] {
+                    Token::Star if c != '/' => { self.stay(&mut next, i); true },
+                    Token::GlobStar { .. } => { self.stay(&mut next, i); true },
+                    Token::Char(x) if same(x, c, self.icase) => { self.enter(&mut next, i + 1); true },
+                    Token::Any if c != '/' => { self.enter(&mut next, i + 1); true },
+                    Token::Class { start, len, negated }
+                        if c != '/' && self.class_match(start, len, c) != negated => {
+                        self.enter(&mut next, i + 1);
+                        true
+                    },
+                    _ => false,
+                };
+            }
+            if !alive { return false; }
+            std::mem::swap(&mut cur, &mut next);
+            next = [false; TOKENS];
+        }
+        cur[self.len]
+    }
+}
+
+impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
+    /// match the whole buffer against a glob pattern, see [Glob] for the syntax.
+    /// An invalid pattern (or one longer than 255 tokens) never match,
+    /// compile it with [Glob::new] to get the error or to match many times
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    ///
+    /// let name: BFRDYN<32> = "report-2024.csv".into();
+    /// assert!(name.glob_match("report-*.csv"));
+    /// assert!(name.glob_match("*-20[0-9][0-9].*"));
+    /// assert!(!name.glob_match("*.txt"));
+    /// ```
+    ///
+    pub fn glob_match(&self, pattern: &str) -> bool {
+        Glob::<256>::new(pattern).is_ok_and(|g| g.is_match(self))
+    }
+}
@@ -12,7 +12,7 @@ pub fn fmt(len: &usize, arr: &[u8], f: &mut std::fmt::Formatter<'_>) -> std::fmt
     write!(f, "{}", &text[0..*len])
 }
 
-/// helper function for From trait implementation
+// helper function for From trait implementation
 // #[doc = "hidden"]
 // pub fn from(value: &str, arr: &mut [u8]) {
 //     assert!(value.len() <= arr.len(), "Not enough capacity!");
@@ -72,18 +72,10 @@ pub fn from_slice2(value: &[u8], arr: &mut [u8]) -> usize {
 } 
 
 #[doc = "hidden"]
-/// helper function for partialEQ trait implementation
+/// helper function for partialEQ trait implementation,
+/// only the first len bytes of each array are compared
 pub fn eq(lena: &usize, arra: &[u8], lenb: &usize, arrb: &[u8]) -> bool {
-    if lena != lenb {
-        return false;
-    } else {
-        for (i, c) in arra.iter().enumerate() {
-            if *c != arrb[i] {
-                return false;
-            }
-        }
-    }
-    true
+    lena == lenb && arra[..*lena] == arrb[..*lenb]
 }
 
 #[doc = "hidden"]
@@ -128,3 +120,32 @@ pub fn checksum(arr: &[u8]) -> usize {
     }
     result
 }
+
+/// FNV-1a 64 bit offset basis
+#[doc = "hidden"]
+pub const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// helper function, fast non cryptographic hash (FNV-1a 64 bit)
+/// to continue hashing from a previous result, pass it as `state`
+#[doc = "hidden"]
+pub const fn fnv1a(state: u64, bytes: &[u8]) -> u64 {
+    let mut hash = state;
+    let mut i = 0;
+    while i < bytes.len() {
+        hash ^= bytes[i] as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+        i += 1;
+    }
+    hash
+}
+
+/// helper function, number of bytes of the utf-8 sequence starting with `first`
+#[doc = "hidden"]
+pub const fn utf8_width(first: u8) -> usize {
+    match first {
+        0xf0.. => 4,
+        0xe0.. => 3,
+        0xc0.. => 2,
+        _ => 1,
+    }
+}
@@ -0,0 +1,226 @@
+use crate::helper;
+use crate::errors as err;
+use crate::table::{BFRTABLE, Id};
+
+/// empty slot of the hash index
+const EMPTY: u32 = u32::MAX;
+
+/// Compact id of an interned string.
+/// Comparing or hashing two symbols is an integer operation,
+/// two symbols from the same interner are equal only if their text is equal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    /// get the raw symbol value
+    pub const fn as_u32(self) -> u32 { self.0 }
+}
+
+/// strings that do not fit into the fixed arena, heap mode only
+#[derive(Debug, Clone, Default)]
+struct Spill {
+    text: String,
+    ends: Vec<usize>,
+    index: Vec<u32>,
+}
+
+impl Spill {
+    fn get(&self, i: usize) -> &str {
+        let start = if i == 0 { 0 } else { self.ends[i - 1] };
+        &self.text[start..self.ends[i]]
+    }
+
+    /// find a string (or the empty slot where it belongs) in the spill index
+    fn probe(&self, text: &str, hash: u64) -> Result<u32, usize> {
+        let mask = self.index.len() - 1;
+        let mut slot = hash as usize & mask;
+        loop {
+            match self.index[slot] {
+                EMPTY => return Err(slot),
+                i if self.get(i as usize) == text => return Ok(i),
+                _ => slot = (slot + 1) & mask,
+            }
+        }
+    }
+
+    fn push(&mut self, text: &str, hash: u64) -> u32 {
+        // keep load factor under 1/2, index len is always a power of two
+        if (self.ends.len() + 1) * 2 > self.index.len() {
+            let size = (self.index.len() * 2).max(16);
+            self.index = vec![EMPTY; size];
+            for i in 0..self.ends.len() {
+                let h = helper::fnv1a(helper::FNV_OFFSET, self.get(i).as_bytes());
+                if let Err(slot) = self.probe(self.get(i), h) { self.index[slot] = i as u32; }
+            }
+        }
+        let i = self.ends.len() as u32;
+        self.text.push_str(text);
+        self.ends.push(self.text.len());
+        if let Err(slot) = self.probe(text, hash) { self.index[slot] = i; }
+        i
+    }
+}
+
+/// Interner stores every distinct string once and gives back a [Symbol] for it.
+/// - BYTES is the capacity of the text arena (on the stack)
+/// - ENTRIES is the maximum number of distinct strings in the arena
+///
+/// The hash index has 2 * ENTRIES slots so lookups stay O(1) even when the arena is full.
+/// By default the interner never allocate and return NotEnoughCapacity
+/// when the arena is full. An interner created with [Interner::with_heap]
+/// keeps going by storing the extra strings on the heap.
+/// # example
+/// ```
+/// use cbfr::intern::Interner;
+/// use cbfr::BFRDYN;
+///
+/// let mut names: Interner<256, 16> = Interner::new();
+/// let a = names.intern("user_id").unwrap();
+/// let key: BFRDYN<32> = "user_id".into();
+/// let b = names.intern(&key).unwrap();
+///
+/// assert_eq!(a, b);   // integer comparison
+/// assert_eq!(Some("user_id"), names.resolve(a));
+/// assert_eq!(1, names.len());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Interner<const BYTES: usize = 4096, const ENTRIES: usize = 256> {
+    table: BFRTABLE<BYTES, ENTRIES>,
+    index: [[u32; 2]; ENTRIES],
+    spill: Option<Spill>,
+}
+
+impl<const BYTES: usize, const ENTRIES: usize> Default for Interner<BYTES, ENTRIES> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<const BYTES: usize, const ENTRIES: usize> Interner<BYTES, ENTRIES> {
+    /// create new interner with fixed capacity, it never allocate
+    pub const fn new() -> Self {
+        Self { table: BFRTABLE::new(), index: [[EMPTY; 2]; ENTRIES], spill: None }
+    }
+
+    /// create new interner that move to the heap once the fixed arena is full.
+    /// Nothing is allocated until then
+    /// # example
+    /// ```
+    /// use cbfr::intern::Interner;
+    ///
+    /// let mut fixed: Interner<8, 2> = Interner::new();
+    /// let mut grow: Interner<8, 2> = Interner::with_heap();
+    /// for word in ["one", "two", "three", "four"] {
+    ///     grow.intern(word).unwrap();
+    /// }
+    /// fixed.intern("one").unwrap();
+    /// fixed.intern("two").unwrap();
+    ///
+    /// assert!(fixed.intern("three").is_err());
+    /// assert_eq!(4, grow.len());
+    /// assert_eq!(Some("four"), grow.get("four").and_then(|s| grow.resolve(s)));
+    /// ```
+    ///
+    pub fn with_heap() -> Self {
+        Self { spill: Some(Spill::default()), ..Self::new() }
+    }
+
+    /// get the number of distinct strings
+    pub fn len(&self) -> usize {
+        self.table.len() + self.spill.as_ref().map_or(0, |s| s.ends.len())
+    }
+
+    /// check if nothing was interned yet
+    pub fn is_empty(&self) -> bool { self.len() == 0 }
+
+    fn slots(&self) -> &[u32] { self.index.as_flattened() }
+
+    /// find a string (or the empty slot where it belongs) in the fixed index.
+    /// The index is never more than half full so an empty slot always exist
+    fn probe(&self, text: &str, hash: u64) -> Result<u32, usize> {
+        if ENTRIES == 0 { return Err(0); }
+        let mut slot = (hash % (2 * ENTRIES) as u64) as usize;
+        loop {
+            match self.slots()[slot] {
+                EMPTY => return Err(slot),
+                i if self.table.get(Id(i as usize)) == Some(text) => return Ok(i),
+                _ => slot = if slot + 1 == 2 * ENTRIES { 0 } else { slot + 1 },
+            }
+        }
+    }
+
+    /// look up the symbol of a string without interning it
+    /// # example
+    /// ```
+    /// use cbfr::intern::Interner;
+    ///
+    /// let mut names: Interner = Interner::new();
+    /// let sym = names.intern("x").unwrap();
+    /// assert_eq!(Some(sym), names.get("x"));
+    /// assert_eq!(None, names.get("y"));
+    /// ```
+    ///
+    pub fn get(&self, text: impl AsRef<str>) -> Option<Symbol> {
+        let text = text.as_ref();
+        let hash = helper::fnv1a(helper::FNV_OFFSET, text.as_bytes());
+        if let Ok(i) = self.probe(text, hash) { return Some(Symbol(i)); }
+        let spill = self.spill.as_ref()?;
+        if spill.index.is_empty() { return None; }
+        spill.probe(text, hash).ok().map(|i| Symbol(ENTRIES as u32 + i))
+    }
+
+    /// return the symbol of a string, storing the string if it is new.
+    /// `text` can be &str, BFRDYN or anything that implement `AsRef<str>`
+    pub fn intern(&mut self, text: impl AsRef<str>) -> Result<Symbol, err::NotEnoughCapacity> {
+        let text = text.as_ref();
+        if let Some(sym) = self.get(text) { return Ok(sym); }
+        let hash = helper::fnv1a(helper::FNV_OFFSET, text.as_bytes());
+        let full = err::NotEnoughCapacity::throw(ENTRIES, ENTRIES + 1);
+        let fixed = match self.probe(text, hash) {
+            Err(slot) if ENTRIES > 0 => self.table.push(text).map(|id| (id, slot)),
+            _ => Err(full),
+        };
+        match (fixed, self.spill.as_mut()) {
+            (Ok((id, slot)), _) => {
+                self.index.as_flattened_mut()[slot] = id.0 as u32;
+                Ok(Symbol(id.0 as u32))
+            },
+            (Err(e), None) => Err(e),
+            (Err(e), Some(spill)) => {
+                if ENTRIES as u64 + spill.ends.len() as u64 >= EMPTY as u64 { return Err(e); }
+                Ok(Symbol(ENTRIES as u32 + spill.push(text, hash)))
+            }
+        }
+    }
+
+    /// get the text of a symbol
+    pub fn resolve(&self, sym: Symbol) -> Option<&str> {
+        let i = sym.0 as usize;
+        if i < ENTRIES {
+            self.table.get(Id(i))
+        } else {
+            let spill = self.spill.as_ref()?;
+            let i = i - ENTRIES;
+            if i < spill.ends.len() { Some(spill.get(i)) } else { None }
+        }
+    }
+
+    /// iterate over every (Symbol, &str) in the order they were interned
+    /// (strings stored on the heap come last)
+    /// # example
+    /// ```
+    /// use cbfr::intern::Interner;
+    ///
+    /// let mut names: Interner = Interner::new();
+    /// for word in ["b", "a", "b"] { names.intern(word).unwrap(); }
+    ///
+    /// let all: Vec<&str> = names.iter().map(|(_, s)| s).collect();
+    /// assert_eq!(vec!["b", "a"], all);
+    /// ```
+    ///
+    pub fn iter(&self) -> impl Iterator<Item = (Symbol, &str)> + '_ {
+        let fixed = self.table.iter().map(|(id, s)| (Symbol(id.0 as u32), s));
+        let spill = self.spill.iter().flat_map(|sp| {
+            (0..sp.ends.len()).map(move |i| (Symbol((ENTRIES + i) as u32), sp.get(i)))
+        });
+        fixed.chain(spill)
+    }
+}
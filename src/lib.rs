@@ -23,14 +23,95 @@
 //! assert_eq!(b3.to_string(), "more string");
 //! ```
 //! # [BFRDYN]
+//! # [BFRRING]
+//! A circular byte buffer, push and pop at both ends in O(1).
+//! # [BFRGAP]
+//! A gap buffer for cursor based text editing.
+//! # [BFRUNDO]
+//! BFRDYN wrapper that record edits so they can be undone and redone.
+//! # [BFRTABLE]
+//! Many strings packed into one fixed byte arena with an offset index.
+//! # [Interner](intern::Interner)
+//! Store each distinct string once and refer to it by a compact symbol.
+//! # [BFRMAP]
+//! Fixed capacity hash map, BFRDYN keys can be looked up with &str.
+//! # [BFRTRIE]
+//! Prefix tree for autocomplete and longest prefix match.
+//! # [LruCache](lru::LruCache)
+//! Fixed capacity least recently used cache with O(1) get, put and evict.
+//! # [Finder](search::Finder)
+//! Precompiled linear time substring search, can be built in const context.
+//! # [scan]
+//! SSE2/AVX2 byte scanning (find_byte, find_any_of_2/3, count_byte) with scalar fallback.
+//! # [AhoCorasick](aho::AhoCorasick)
+//! Search many patterns in one pass, fixed tables, const constructible.
+//! # [Regex](regex::Regex)
+//! Small regex engine (Pike VM) with captures, compiled into a fixed size program.
+//! # [Glob](glob::Glob)
+//! Shell style wildcards (`*`, `?`, `[a-z]`, `**`) matched in linear time.
+//! # [distance]
+//! Levenshtein, Damerau-Levenshtein, Hamming, Jaro-Winkler and LCS over chars, no allocation.
+//! # [fuzzy]
+//! fzf style fuzzy scoring with highlight positions and a fixed capacity top-K ranking.
+//! # [pad]
+//! pad_left, pad_right, center, truncate_with_ellipsis and fit, in chars or [display columns](width).
+//! # [Wrap](wrap::Wrap)
+//! Word wrapping in display columns with indents and hyphen breaks, as an allocation free line iterator or in place.
+//! # [case]
+//! snake_case, camelCase, PascalCase, kebab-case, SCREAMING_SNAKE_CASE and Train-Case conversions.
+//! # [translit]
+//! ASCII transliteration of Latin-1 and Latin Extended-A text and URL slugs.
+//! # [escape]
+//! JSON, HTML, C/Rust literal escaping and unescaping, and POSIX shell quoting.
+//! # [url]
+//! Percent encoding with WHATWG encode sets, query string parsing and building.
+//! # [codec]
+//! Hex, Base64 and Base32 encoding of bytes, with constant time decoding.
+//! # [num]
+//! Integer and float formatting and parsing in place, with radix, padding and digit grouping.
+//! # [BFRBIN]
+//! Byte frame for binary protocols: LE/BE integers, LEB128 varints and length prefixed strings with a read cursor.
 
 
 pub mod prelude;
 pub mod cb;
 pub mod helper;
 pub mod errors;
+pub mod ring;
+pub mod gap;
+pub mod undo;
+pub mod table;
+pub mod intern;
+pub mod map;
+pub mod trie;
+pub mod lru;
+pub mod search;
+pub mod scan;
+pub mod aho;
+pub mod regex;
+pub mod glob;
+pub mod distance;
+pub mod fuzzy;
+pub mod pattern;
+pub mod width;
+pub mod pad;
+pub mod wrap;
+pub mod case;
+pub mod translit;
+pub mod escape;
+pub mod url;
+pub mod codec;
+pub mod num;
+pub mod binary;
 
 /// Re-exports
 pub use cb::BFRDYN;
 pub use cb::DEFCAPACITY;
+pub use ring::BFRRING;
+pub use gap::BFRGAP;
+pub use undo::BFRUNDO;
+pub use table::BFRTABLE;
+pub use map::BFRMAP;
+pub use trie::BFRTRIE;
+pub use binary::BFRBIN;
 
@@ -0,0 +1,384 @@
+use std::borrow::Borrow;
+use std::fmt::Debug;
+use std::hash::{BuildHasher, Hash};
+
+use crate::cb::BFRDYN;
+use crate::map::FnvBuildHasher;
+
+/// no entry
+const NIL: u32 = u32::MAX;
+
+/// called with every entry pushed out of the cache by [LruCache::put]
+pub type EvictFn<K, V> = fn(K, V);
+
+/// links of an entry in the recency list
+#[derive(Debug, Clone, Copy)]
+struct Link {
+    prev: u32,
+    next: u32,
+    hash: u64,
+}
+
+/// LruCache keep the CAP most recently used entries and evict the least
+/// recently used one when a new key comes in. Everything live in fixed arrays:
+/// the recency list is a doubly linked list of indices and the hash index has
+/// 2 * CAP slots, so get, put and evict are O(1) without heap allocation.
+/// - K is the key, usually BFRDYN (see [LruBfr]), looked up with &str through `Borrow<str>`
+/// - CAP is the maximum number of entries
+/// - S is the hasher builder, [FnvHasher](crate::map::FnvHasher) by default
+/// # example
+/// ```
+/// use cbfr::lru::LruCache;
+/// use cbfr::BFRDYN;
+///
+/// let mut dns: LruCache<BFRDYN<32>, [u8; 4], 2> = LruCache::new();
+/// dns.put("example.com".into(), [93, 184, 216, 34]);
+/// dns.put("localhost".into(), [127, 0, 0, 1]);
+/// dns.get("example.com");                        // example.com is now most recent
+/// dns.put("rust-lang.org".into(), [13, 35, 3, 7]); // evict localhost
+///
+/// assert!(dns.get("localhost").is_none());
+/// assert_eq!(Some(&[93, 184, 216, 34]), dns.get("example.com"));
+/// assert_eq!((2, 1), (dns.hits(), dns.misses()));
+/// ```
+#[derive(Clone)]
+pub struct LruCache<K, V, const CAP: usize, S = FnvBuildHasher> {
+    entries: [Option<(K, V)>; CAP],
+    links: [Link; CAP],
+    index: [[u32; 2]; CAP],
+    head: u32,
+    tail: u32,
+    free: u32,
+    len: usize,
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+    on_evict: Option<EvictFn<K, V>>,
+    hasher: S,
+}
+
+/// LruCache keyed by BFRDYN<N>
+pub type LruBfr<V, const CAP: usize, const N: usize = 64> = LruCache<BFRDYN<N>, V, CAP>;
+
+impl<K: Debug, V: Debug, const CAP: usize, S> Debug for LruCache<K, V, CAP, S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K: Hash + Eq, V, const CAP: usize, S: BuildHasher + Default> Default for LruCache<K, V, CAP, S> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<K: Hash + Eq, V, const CAP: usize, S: BuildHasher + Default> LruCache<K, V, CAP, S> {
+    /// create new empty cache
+    pub fn new() -> Self { Self::with_hasher(S::default()) }
+}
+
+impl<K: Hash + Eq, V, const CAP: usize, S: BuildHasher> LruCache<K, V, CAP, S> {
+    /// create new empty cache with the given hasher builder
+    pub fn with_hasher(hasher: S) -> Self {
+        let mut links = [Link { prev: NIL, next: NIL, hash: 0 }; CAP];
+        // every entry start in the free list, chained by `next`
+        for (i, l) in links.iter_mut().enumerate() {
+            l.next = if i + 1 < CAP { (i + 1) as u32 } else { NIL };
+        }
+        Self {
+            entries: std::array::from_fn(|_| None),
+            links,
+            index: [[NIL; 2]; CAP],
+            head: NIL,
+            tail: NIL,
+            free: if CAP == 0 { NIL } else { 0 },
+            len: 0,
+            hits: 0,
+            misses: 0,
+            evictions: 0,
+            on_evict: None,
+            hasher,
+        }
+    }
+
+    /// set a function called with every entry evicted by [LruCache::put]
+    /// # example
+    /// ```
+    /// use cbfr::lru::LruCache;
+    ///
+    /// let mut c: LruCache<u32, String, 1> = LruCache::new();
+    /// c.set_on_evict(Some(|k, v| println!("drop {k} = {v}")));
+    /// c.put(1, "one".to_string());
+    /// c.put(2, "two".to_string());  // print "drop 1 = one"
+    /// assert_eq!(1, c.evictions());
+    /// ```
+    ///
+    pub fn set_on_evict(&mut self, hook: Option<EvictFn<K, V>>) { self.on_evict = hook; }
+
+    /// get the maximum number of entries
+    pub const fn capacity(&self) -> usize { CAP }
+
+    /// get the number of entries
+    pub const fn len(&self) -> usize { self.len }
+
+    /// check if cache is empty
+    pub const fn is_empty(&self) -> bool { self.len == 0 }
+
+    /// number of lookups (get, get_mut) that found their key
+    pub const fn hits(&self) -> u64 { self.hits }
+
+    /// number of lookups (get, get_mut) that didn't find their key
+    pub const fn misses(&self) -> u64 { self.misses }
+
+    /// number of entries pushed out by a new key
+    pub const fn evictions(&self) -> u64 { self.evictions }
+
+    /// set hits, misses and evictions back to 0
+    pub fn reset_stats(&mut self) {
+        self.hits = 0;
+        self.misses = 0;
+        self.evictions = 0;
+    }
+
+    fn slots(&self) -> &[u32] { self.index.as_flattened() }
+
+    fn slots_mut(&mut self) -> &mut [u32] { self.index.as_flattened_mut() }
+
+    /// ideal index slot of a hash
+    const fn home(hash: u64) -> usize { (hash % (2 * CAP) as u64) as usize }
+
+    const fn next_slot(slot: usize) -> usize {
+        if slot + 1 == 2 * CAP { 0 } else { slot + 1 }
+    }
+
+    /// find the index slot of a key, or the empty slot where it belongs.
+    /// The index is never more than half full so an empty slot always exist
+    fn probe<Q>(&self, key: &Q, hash: u64) -> Result<usize, usize>
+    where K: Borrow<Q>, Q: Hash + Eq + ?Sized
+    {
+        let mut slot = Self::home(hash);
+        loop {
+            match self.slots()[slot] {
+                NIL => return Err(slot),
+                e => {
+                    let found = self.links[e as usize].hash == hash
+                        && self.entries[e as usize].as_ref().is_some_and(|(k, _)| k.borrow() == key);
+                    if found { return Ok(slot); }
+                }
+            }
+            slot = Self::next_slot(slot);
+        }
+    }
+
+    /// find the entry of a key
+    fn find<Q>(&self, key: &Q) -> Option<u32>
+    where K: Borrow<Q>, Q: Hash + Eq + ?Sized
+    {
+        if CAP == 0 { return None; }
+        let hash = self.hasher.hash_one(key);
+        self.probe(key, hash).ok().map(|slot| self.slots()[slot])
+    }
+
+    /// empty an index slot and shift back the following slots (no tombstone)
+    fn unindex(&mut self, mut hole: usize) {
+        self.slots_mut()[hole] = NIL;
+        let mut slot = Self::next_slot(hole);
+        while self.slots()[slot] != NIL {
+            let e = self.slots()[slot];
+            let home = Self::home(self.links[e as usize].hash);
+            let between = if hole <= slot {
+                home <= hole || home > slot
+            } else {
+                home <= hole && home > slot
+            };
+            if between {
+                self.slots_mut()[hole] = e;
+                self.slots_mut()[slot] = NIL;
+                hole = slot;
+            }
+            slot = Self::next_slot(slot);
+        }
+    }
+
+    fn unlink(&mut self, e: u32) {
+        let Link { prev, next, .. } = self.links[e as usize];
+        if prev == NIL { self.head = next; } else { self.links[prev as usize].next = next; }
+        if next == NIL { self.tail = prev; } else { self.links[next as usize].prev = prev; }
+    }
+
+    fn link_front(&mut self, e: u32) {
+        self.links[e as usize].prev = NIL;
+        self.links[e as usize].next = self.head;
+        if self.head == NIL { self.tail = e; } else { self.links[self.head as usize].prev = e; }
+        self.head = e;
+    }
+
+    fn touch(&mut self, e: u32) {
+        if self.head != e {
+            self.unlink(e);
+            self.link_front(e);
+        }
+    }
+
+    /// take an entry out of the cache, its slot go back to the free list
+    fn take(&mut self, e: u32) -> (K, V) {
+        let hash = self.links[e as usize].hash;
+        let slot = {
+            let (k, _) = self.entries[e as usize].as_ref().expect("linked entry");
+            self.probe(k, hash).expect("linked entry is indexed")
+        };
+        self.unindex(slot);
+        self.unlink(e);
+        self.links[e as usize].next = self.free;
+        self.free = e;
+        self.len -= 1;
+        self.entries[e as usize].take().expect("linked entry")
+    }
+
+    /// get the value of a key and mark it as most recently used
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where K: Borrow<Q>, Q: Hash + Eq + ?Sized
+    {
+        self.get_mut(key).map(|v| &*v)
+    }
+
+    /// get the value of a key as mutable and mark it as most recently used
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where K: Borrow<Q>, Q: Hash + Eq + ?Sized
+    {
+        let Some(e) = self.find(key) else {
+            self.misses += 1;
+            return None;
+        };
+        self.hits += 1;
+        self.touch(e);
+        self.entries[e as usize].as_mut().map(|(_, v)| v)
+    }
+
+    /// get the value of a key without changing its recency or the counters
+    pub fn peek<Q>(&self, key: &Q) -> Option<&V>
+    where K: Borrow<Q>, Q: Hash + Eq + ?Sized
+    {
+        let e = self.find(key)?;
+        self.entries[e as usize].as_ref().map(|(_, v)| v)
+    }
+
+    /// check if the cache has a key, without changing its recency or the counters
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where K: Borrow<Q>, Q: Hash + Eq + ?Sized
+    {
+        self.find(key).is_some()
+    }
+
+    /// insert a key value pair as most recently used, return the evicted pair:
+    /// the old pair of the same key, or the least recently used one if cache was full
+    /// # example
+    /// ```
+    /// use cbfr::lru::LruCache;
+    ///
+    /// let mut c: LruCache<&str, i32, 2> = LruCache::new();
+    /// assert_eq!(None, c.push("a", 1));
+    /// assert_eq!(Some(("a", 1)), c.push("a", 2));
+    /// c.push("b", 3);
+    /// assert_eq!(Some(("a", 2)), c.push("c", 4));
+    /// ```
+    ///
+    pub fn push(&mut self, key: K, value: V) -> Option<(K, V)> {
+        if CAP == 0 { return Some((key, value)); }
+        let hash = self.hasher.hash_one(&key);
+        if let Ok(slot) = self.probe(&key, hash) {
+            let e = self.slots()[slot];
+            self.touch(e);
+            return self.entries[e as usize].replace((key, value));
+        }
+        let evicted = if self.len == CAP {
+            self.evictions += 1;
+            Some(self.take(self.tail))
+        } else {
+            None
+        };
+        let e = self.free;
+        self.free = self.links[e as usize].next;
+        self.links[e as usize].hash = hash;
+        self.entries[e as usize] = Some((key, value));
+        self.link_front(e);
+        // probe again, eviction may have shifted the index
+        let mut slot = Self::home(hash);
+        while self.slots()[slot] != NIL { slot = Self::next_slot(slot); }
+        self.slots_mut()[slot] = e;
+        self.len += 1;
+        evicted
+    }
+
+    /// insert a key value pair as most recently used, return the old value of the key.
+    /// If a least recently used entry had to go, it is passed to the eviction hook
+    /// # example
+    /// ```
+    /// use cbfr::lru::LruBfr;
+    ///
+    /// let mut c: LruBfr<u32, 2, 16> = LruBfr::new();
+    /// c.put("a".into(), 1);
+    /// assert_eq!(Some(1), c.put("a".into(), 2));
+    /// c.put("b".into(), 3);
+    /// c.put("c".into(), 4);
+    ///
+    /// assert!(!c.contains_key("a"));
+    /// assert_eq!(1, c.evictions());
+    /// ```
+    ///
+    pub fn put(&mut self, key: K, value: V) -> Option<V> {
+        let replaced = self.contains_key(&key);
+        let out = self.push(key, value)?;
+        if replaced { return Some(out.1); }
+        if let Some(hook) = self.on_evict { hook(out.0, out.1); }
+        None
+    }
+
+    /// remove a key and return its value
+    pub fn pop<Q>(&mut self, key: &Q) -> Option<V>
+    where K: Borrow<Q>, Q: Hash + Eq + ?Sized
+    {
+        let e = self.find(key)?;
+        Some(self.take(e).1)
+    }
+
+    /// remove and return the least recently used entry
+    pub fn pop_lru(&mut self) -> Option<(K, V)> {
+        if self.tail == NIL { return None; }
+        Some(self.take(self.tail))
+    }
+
+    /// get the least recently used entry, the next one to be evicted
+    pub fn peek_lru(&self) -> Option<(&K, &V)> {
+        if self.tail == NIL { return None; }
+        self.entries[self.tail as usize].as_ref().map(|(k, v)| (k, v))
+    }
+
+    /// remove every entries, counters are kept
+    pub fn clear(&mut self) {
+        while self.pop_lru().is_some() {}
+    }
+}
+
+impl<K, V, const CAP: usize, S> LruCache<K, V, CAP, S> {
+    /// iterate over (&key, &value) from most to least recently used
+    /// # example
+    /// ```
+    /// use cbfr::lru::LruCache;
+    ///
+    /// let mut c: LruCache<u8, (), 4> = LruCache::new();
+    /// for k in [1, 2, 3] { c.put(k, ()); }
+    /// c.get(&1);
+    ///
+    /// let order: Vec<u8> = c.iter().map(|(k, _)| *k).collect();
+    /// assert_eq!(vec![1, 3, 2], order);
+    /// ```
+    ///
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> + '_ {
+        let mut e = self.head;
+        std::iter::from_fn(move || {
+            if e == NIL { return None; }
+            let (k, v) = self.entries[e as usize].as_ref()?;
+            e = self.links[e as usize].next;
+            Some((k, v))
+        })
+    }
+}
@@ -57,7 +57,7 @@ fn demo2() {
 
     let myb: BFRDYN = "Test".into();
     let mut by = myb.as_bytes();
-    by[0] = 'Z' as u8;
+    by[0] = b'Z';
 
     println!("myb: {myb}");
     println!("by: {by:?}");
@@ -0,0 +1,426 @@
+use std::borrow::Borrow;
+use std::fmt::Debug;
+use std::hash::{BuildHasher, BuildHasherDefault, Hash, Hasher};
+
+use crate::helper;
+use crate::errors as err;
+
+/// Fast non cryptographic hasher (FNV-1a 64 bit), good for short keys
+/// like BFRDYN but not resistant to hash flooding
+#[derive(Debug, Clone, Copy)]
+pub struct FnvHasher(u64);
+
+impl Default for FnvHasher {
+    fn default() -> Self { Self(helper::FNV_OFFSET) }
+}
+
+impl Hasher for FnvHasher {
+    fn finish(&self) -> u64 { self.0 }
+    fn write(&mut self, bytes: &[u8]) { self.0 = helper::fnv1a(self.0, bytes); }
+}
+
+/// BuildHasher of [FnvHasher], the default hasher of BFRMAP
+pub type FnvBuildHasher = BuildHasherDefault<FnvHasher>;
+
+/// BFRMAP is a fixed capacity hash map that live entirely on the stack.
+/// It use open addressing with linear probing, removed entries are not
+/// marked with tombstone, the entries after them are shifted back instead,
+/// so lookup never slows down after many removals.
+/// - N is the maximum number of entries
+/// - S is the hasher builder, [FnvHasher] by default
+///
+/// Keys are usually BFRDYN, they can be searched with &str through `Borrow<str>`.
+/// # example
+/// ```
+/// use cbfr::map::BFRMAP;
+/// use cbfr::BFRDYN;
+///
+/// let mut ages: BFRMAP<BFRDYN<16>, u32, 8> = BFRMAP::new();
+/// ages.insert("alice".into(), 31).unwrap();
+/// ages.insert("bob".into(), 27).unwrap();
+///
+/// assert_eq!(Some(&31), ages.get("alice"));
+/// assert_eq!(Some(27), ages.remove("bob"));
+/// assert_eq!(1, ages.len());
+/// ```
+#[derive(Clone)]
+pub struct BFRMAP<K, V, const N: usize, S = FnvBuildHasher> {
+    slots: [Option<(K, V)>; N],
+    len: usize,
+    hasher: S,
+}
+
+impl<K: Debug, V: Debug, const N: usize, S> Debug for BFRMAP<K, V, N, S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K: Hash + Eq, V, const N: usize, S: BuildHasher + Default> Default for BFRMAP<K, V, N, S> {
+    fn default() -> Self { Self::new() }
+}
+
+/// A view into a single entry of BFRMAP, see [BFRMAP::entry]
+pub enum Entry<'a, K, V, const N: usize, S> {
+    Occupied(OccupiedEntry<'a, K, V, N, S>),
+    Vacant(VacantEntry<'a, K, V, N, S>),
+}
+
+/// An entry that hold a value
+pub struct OccupiedEntry<'a, K, V, const N: usize, S> {
+    map: &'a mut BFRMAP<K, V, N, S>,
+    slot: usize,
+}
+
+/// An empty entry, inserting into it never fail
+pub struct VacantEntry<'a, K, V, const N: usize, S> {
+    map: &'a mut BFRMAP<K, V, N, S>,
+    slot: usize,
+    key: K,
+}
+
+impl<K: Hash + Eq, V, const N: usize, S: BuildHasher + Default> BFRMAP<K, V, N, S> {
+    /// create new empty map
+    /// # example
+    /// ```
+    /// use cbfr::map::BFRMAP;
+    /// use cbfr::BFRDYN;
+    ///
+    /// let m: BFRMAP<BFRDYN<16>, u8, 32> = BFRMAP::new();
+    /// assert_eq!(0, m.len());
+    /// assert_eq!(32, m.capacity());
+    /// ```
+    ///
+    pub fn new() -> Self { Self::with_hasher(S::default()) }
+}
+
+impl<K: Hash + Eq, V, const N: usize, S: BuildHasher> BFRMAP<K, V, N, S> {
+    /// create new empty map with the given hasher builder
+    /// # example
+    /// ```
+    /// use std::collections::hash_map::RandomState;
+    /// use cbfr::map::BFRMAP;
+    ///
+    /// let mut m: BFRMAP<u32, u32, 8, RandomState> = BFRMAP::with_hasher(RandomState::new());
+    /// m.insert(1, 10).unwrap();
+    /// assert_eq!(Some(&10), m.get(&1));
+    /// ```
+    ///
+    pub fn with_hasher(hasher: S) -> Self {
+        Self { slots: std::array::from_fn(|_| None), len: 0, hasher }
+    }
+
+    /// get the maximum number of entries
+    pub const fn capacity(&self) -> usize { N }
+
+    /// get the number of entries
+    pub const fn len(&self) -> usize { self.len }
+
+    /// check if map is empty
+    pub const fn is_empty(&self) -> bool { self.len == 0 }
+
+    /// ideal slot of a key
+    fn home<Q: Hash + ?Sized>(&self, key: &Q) -> usize {
+        (self.hasher.hash_one(key) % N as u64) as usize
+    }
+
+    const fn next(slot: usize) -> usize {
+        if slot + 1 == N { 0 } else { slot + 1 }
+    }
+
+    /// find the slot of a key, or the empty slot where it belongs (None when full)
+    fn probe<Q>(&self, key: &Q) -> Result<usize, Option<usize>>
+    where K: Borrow<Q>, Q: Hash + Eq + ?Sized
+    {
+        if N == 0 { return Err(None); }
+        let mut slot = self.home(key);
+        for _ in 0..N {
+            match &self.slots[slot] {
+                None => return Err(Some(slot)),
+                Some((k, _)) if k.borrow() == key => return Ok(slot),
+                _ => slot = Self::next(slot),
+            }
+        }
+        Err(None)
+    }
+
+    /// insert a key value pair, return the old value if the key was already there.
+    /// Return NotEnoughCapacity if the key is new and the map is full
+    /// # example
+    /// ```
+    /// use cbfr::map::BFRMAP;
+    /// use cbfr::BFRDYN;
+    ///
+    /// let mut m: BFRMAP<BFRDYN<8>, i32, 2> = BFRMAP::new();
+    /// assert_eq!(None, m.insert("a".into(), 1).unwrap());
+    /// assert_eq!(Some(1), m.insert("a".into(), 2).unwrap());
+    /// m.insert("b".into(), 3).unwrap();
+    /// assert!(m.insert("c".into(), 4).is_err());
+    /// ```
+    ///
+    pub fn insert(&mut self, key: K, value: V) -> Result<Option<V>, err::NotEnoughCapacity> {
+        match self.probe(&key) {
+            Ok(slot) => {
+                let (_, v) = self.slots[slot].as_mut().expect("probe returned occupied slot");
+                Ok(Some(std::mem::replace(v, value)))
+            },
+            Err(Some(slot)) => {
+                self.slots[slot] = Some((key, value));
+                self.len += 1;
+                Ok(None)
+            },
+            Err(None) => Err(err::NotEnoughCapacity::throw(N, self.len + 1)),
+        }
+    }
+
+    /// get a reference to the value of a key
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where K: Borrow<Q>, Q: Hash + Eq + ?Sized
+    {
+        let slot = self.probe(key).ok()?;
+        self.slots[slot].as_ref().map(|(_, v)| v)
+    }
+
+    /// get a mutable reference to the value of a key
+    /// # example
+    /// ```
+    /// use cbfr::map::BFRMAP;
+    /// use cbfr::BFRDYN;
+    ///
+    /// let mut m: BFRMAP<BFRDYN<8>, i32, 4> = BFRMAP::new();
+    /// m.insert("hits".into(), 1).unwrap();
+    /// *m.get_mut("hits").unwrap() += 1;
+    /// assert_eq!(Some(&2), m.get("hits"));
+    /// ```
+    ///
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where K: Borrow<Q>, Q: Hash + Eq + ?Sized
+    {
+        let slot = self.probe(key).ok()?;
+        self.slots[slot].as_mut().map(|(_, v)| v)
+    }
+
+    /// check if the map has a key
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where K: Borrow<Q>, Q: Hash + Eq + ?Sized
+    {
+        self.probe(key).is_ok()
+    }
+
+    /// remove a key and return its value
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where K: Borrow<Q>, Q: Hash + Eq + ?Sized
+    {
+        self.remove_entry(key).map(|(_, v)| v)
+    }
+
+    /// remove a key and return the stored key value pair.
+    /// Entries that were pushed away by this key are shifted back
+    /// to keep every probe sequence without hole
+    /// # example
+    /// ```
+    /// use cbfr::map::BFRMAP;
+    ///
+    /// let mut m: BFRMAP<u32, u32, 4> = BFRMAP::new();
+    /// for i in 0..4 { m.insert(i, i * 10).unwrap(); }
+    /// for i in 0..4 {
+    ///     assert_eq!(Some((i, i * 10)), m.remove_entry(&i));
+    ///     for j in i + 1..4 { assert_eq!(Some(&(j * 10)), m.get(&j)); }
+    /// }
+    /// assert!(m.is_empty());
+    /// ```
+    ///
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where K: Borrow<Q>, Q: Hash + Eq + ?Sized
+    {
+        let slot = self.probe(key).ok()?;
+        Some(self.remove_slot(slot))
+    }
+
+    /// take the entry out of a slot, then shift back the entries that
+    /// were pushed away by it to keep every probe sequence without hole
+    fn remove_slot(&mut self, slot: usize) -> (K, V) {
+        let removed = self.slots[slot].take().expect("occupied slot");
+        self.len -= 1;
+        let mut hole = slot;
+        let mut slot = Self::next(hole);
+        while let Some((k, _)) = &self.slots[slot] {
+            let home = self.home(k);
+            // move the entry back if the hole lies between its home and its slot
+            let between = if hole <= slot {
+                home <= hole || home > slot
+            } else {
+                home <= hole && home > slot
+            };
+            if between {
+                self.slots[hole] = self.slots[slot].take();
+                hole = slot;
+            }
+            slot = Self::next(slot);
+        }
+        removed
+    }
+
+    /// get the entry of a key for in place manipulation.
+    /// Return NotEnoughCapacity if the key is new and the map is full
+    /// # example
+    /// ```
+    /// use cbfr::map::BFRMAP;
+    /// use cbfr::BFRDYN;
+    ///
+    /// let mut words: BFRMAP<BFRDYN<16>, usize, 16> = BFRMAP::new();
+    /// for w in "a rose is a rose".split(' ') {
+    ///     *words.entry(w.into()).unwrap().or_insert(0) += 1;
+    /// }
+    /// assert_eq!(Some(&2), words.get("rose"));
+    /// assert_eq!(Some(&1), words.get("is"));
+    /// ```
+    ///
+    pub fn entry(&mut self, key: K) -> Result<Entry<'_, K, V, N, S>, err::NotEnoughCapacity> {
+        match self.probe(&key) {
+            Ok(slot) => Ok(Entry::Occupied(OccupiedEntry { map: self, slot })),
+            Err(Some(slot)) => Ok(Entry::Vacant(VacantEntry { map: self, slot, key })),
+            Err(None) => Err(err::NotEnoughCapacity::throw(N, self.len + 1)),
+        }
+    }
+
+    /// remove every entries
+    pub fn clear(&mut self) {
+        for slot in self.slots.iter_mut() { *slot = None; }
+        self.len = 0;
+    }
+}
+
+impl<K, V, const N: usize, S> BFRMAP<K, V, N, S> {
+    /// iterate over (&key, &value) in slot order
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> + '_ {
+        self.slots.iter().filter_map(|s| s.as_ref().map(|(k, v)| (k, v)))
+    }
+
+    /// iterate over (&key, &mut value) in slot order
+    /// # example
+    /// ```
+    /// use cbfr::map::BFRMAP;
+    ///
+    /// let mut m: BFRMAP<u8, u32, 8> = BFRMAP::new();
+    /// m.insert(1, 1).unwrap();
+    /// m.insert(2, 2).unwrap();
+    /// for (_, v) in m.iter_mut() { *v *= 10; }
+    ///
+    /// let mut all: Vec<u32> = m.values().copied().collect();
+    /// all.sort();
+    /// assert_eq!(vec![10, 20], all);
+    /// ```
+    ///
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&K, &mut V)> + '_ {
+        self.slots.iter_mut().filter_map(|s| s.as_mut().map(|(k, v)| (&*k, v)))
+    }
+
+    /// iterate over keys
+    pub fn keys(&self) -> impl Iterator<Item = &K> + '_ { self.iter().map(|(k, _)| k) }
+
+    /// iterate over values
+    pub fn values(&self) -> impl Iterator<Item = &V> + '_ { self.iter().map(|(_, v)| v) }
+}
+
+impl<'a, K, V, const N: usize, S> Entry<'a, K, V, N, S> {
+    /// get the key of this entry
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(e) => e.key(),
+            Entry::Vacant(e) => &e.key,
+        }
+    }
+
+    /// return the value, insert `default` first if the entry is vacant
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        match self {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => e.insert(default),
+        }
+    }
+
+    /// return the value, insert the result of `default` first if the entry is vacant
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => e.insert(default()),
+        }
+    }
+
+    /// modify the value if the entry is occupied
+    /// # example
+    /// ```
+    /// use cbfr::map::BFRMAP;
+    ///
+    /// let mut m: BFRMAP<u8, u32, 8> = BFRMAP::new();
+    /// m.entry(1).unwrap().and_modify(|v| *v += 1).or_insert(10);
+    /// m.entry(1).unwrap().and_modify(|v| *v += 1).or_insert(10);
+    /// assert_eq!(Some(&11), m.get(&1));
+    /// ```
+    ///
+    pub fn and_modify<F: FnOnce(&mut V)>(self, f: F) -> Self {
+        match self {
+            Entry::Occupied(mut e) => {
+                f(e.get_mut());
+                Entry::Occupied(e)
+            },
+            Entry::Vacant(e) => Entry::Vacant(e),
+        }
+    }
+}
+
+impl<'a, K, V: Default, const N: usize, S> Entry<'a, K, V, N, S> {
+    /// return the value, insert V::default() first if the entry is vacant
+    pub fn or_default(self) -> &'a mut V {
+        self.or_insert_with(V::default)
+    }
+}
+
+impl<'a, K, V, const N: usize, S> OccupiedEntry<'a, K, V, N, S> {
+    fn pair(&self) -> &(K, V) {
+        self.map.slots[self.slot].as_ref().expect("occupied entry")
+    }
+
+    fn pair_mut(&mut self) -> &mut (K, V) {
+        self.map.slots[self.slot].as_mut().expect("occupied entry")
+    }
+
+    /// get the key of this entry
+    pub fn key(&self) -> &K { &self.pair().0 }
+
+    /// get the value
+    pub fn get(&self) -> &V { &self.pair().1 }
+
+    /// get the value as mutable
+    pub fn get_mut(&mut self) -> &mut V { &mut self.pair_mut().1 }
+
+    /// get the value as mutable with the lifetime of the map
+    pub fn into_mut(self) -> &'a mut V {
+        &mut self.map.slots[self.slot].as_mut().expect("occupied entry").1
+    }
+
+    /// replace the value, return the old one
+    pub fn insert(&mut self, value: V) -> V {
+        std::mem::replace(self.get_mut(), value)
+    }
+}
+
+impl<'a, K: Hash + Eq, V, const N: usize, S: BuildHasher> OccupiedEntry<'a, K, V, N, S> {
+    /// remove the entry from the map, return the value
+    pub fn remove(self) -> V {
+        self.map.remove_slot(self.slot).1
+    }
+}
+
+impl<'a, K, V, const N: usize, S> VacantEntry<'a, K, V, N, S> {
+    /// get the key that would be used when inserting
+    pub fn key(&self) -> &K { &self.key }
+
+    /// insert the value into the map, return it as mutable
+    pub fn insert(self, value: V) -> &'a mut V {
+        self.map.slots[self.slot] = Some((self.key, value));
+        self.map.len += 1;
+        &mut self.map.slots[self.slot].as_mut().expect("just inserted").1
+    }
+}
@@ -0,0 +1,517 @@
+//! Number formatting and parsing directly in a BFRDYN, without going through a String.
+//! Integers are written in any radix with zero padding, sign and digit grouping ([IntFormat]),
+//! floats in their shortest roundtrip form or with a fixed precision ([FloatFormat]).
+//! A failed append leave the buffer unchanged, parse errors give the position of the bad char.
+//! # example
+//! ```
+//! use cbfr::BFRDYN;
+//! use cbfr::num::{IntFormat, FloatFormat};
+//!
+//! let mut b: BFRDYN<64> = BFRDYN::new();
+//! b.append_str("id=").unwrap();
+//! b.append_int(42u8).unwrap();
+//! b.append_str(" mask=0x").unwrap();
+//! b.append_int_with(0xbeefu32, &IntFormat::new().radix(16).zero_pad(8)).unwrap();
+//! b.append_str(" total=").unwrap();
+//! b.append_float_with(1234567.891, &FloatFormat::new().precision(2).thousands(',')).unwrap();
+//! assert_eq!("id=42 mask=0x0000beef total=1,234,567.89", b.as_str());
+//!
+//! let n: BFRDYN<16> = "-1250".into();
+//! assert_eq!(-1250i32, n.parse().unwrap());
+//! assert_eq!(1, n.parse::<u32>().unwrap_err().index());
+//! ```
+
+use std::fmt::{self, Write};
+
+use crate::cb::{BFRDYN, NecResult};
+use crate::errors as err;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Primitive integer types, see [BFRDYN::append_int] and [BFRDYN::parse_int]
+pub trait Integer: Copy + sealed::Sealed {
+    /// sign and magnitude
+    #[doc(hidden)]
+    fn to_parts(self) -> (bool, u128);
+    /// largest magnitude with that sign
+    #[doc(hidden)]
+    fn max_magnitude(negative: bool) -> u128;
+    /// value from a sign and a magnitude in range
+    #[doc(hidden)]
+    fn from_parts(negative: bool, magnitude: u128) -> Self;
+}
+
+/// Primitive float types, see [BFRDYN::append_float]
+pub trait Float: Copy + fmt::Display + sealed::Sealed {}
+
+/// Types read by [BFRDYN::parse]: every integer and float primitive
+pub trait Parse: Sized + sealed::Sealed {
+    #[doc(hidden)]
+    fn parse_str(s: &str) -> Result<Self, err::InvalidFormat>;
+}
+
+macro_rules! impl_unsigned {
+    ($($t:ty),*) => {$(
+        impl sealed::Sealed for $t {}
+        impl Integer for $t {
+            fn to_parts(self) -> (bool, u128) { (false, self as u128) }
+            fn max_magnitude(negative: bool) -> u128 { if negative { 0 } else { <$t>::MAX as u128 } }
+            fn from_parts(_: bool, magnitude: u128) -> Self { magnitude as $t }
+        }
+        impl Parse for $t {
+            fn parse_str(s: &str) -> Result<Self, err::InvalidFormat> { parse_int(s, 10) }
+        }
+    )*};
+}
+
+macro_rules! impl_signed {
+    ($($t:ty),*) => {$(
+        impl sealed::Sealed for $t {}
+        impl Integer for $t {
+            fn to_parts(self) -> (bool, u128) { (self < 0, self.unsigned_abs() as u128) }
+            fn max_magnitude(negative: bool) -> u128 { <$t>::MAX as u128 + negative as u128 }
+            fn from_parts(negative: bool, magnitude: u128) -> Self {
+                if negative { (magnitude as $t).wrapping_neg() } else { magnitude as $t }
+            }
+        }
+        impl Parse for $t {
+            fn parse_str(s: &str) -> Result<Self, err::InvalidFormat> { parse_int(s, 10) }
+        }
+    )*};
+}
+
+macro_rules! impl_float {
+    ($($t:ty),*) => {$(
+        impl sealed::Sealed for $t {}
+        impl Float for $t {}
+        impl Parse for $t {
+            fn parse_str(s: &str) -> Result<Self, err::InvalidFormat> {
+                check_float(s)?;
+                s.parse().map_err(|_| err::InvalidFormat::throw(0, "invalid float"))
+            }
+        }
+    )*};
+}
+
+impl_unsigned!(u8, u16, u32, u64, u128, usize);
+impl_signed!(i8, i16, i32, i64, i128, isize);
+impl_float!(f32, f64);
+
+/// Integer formatting options
+/// # example
+/// ```
+/// use cbfr::BFRDYN;
+/// use cbfr::num::IntFormat;
+///
+/// let mut b: BFRDYN<32> = BFRDYN::new();
+/// b.append_int_with(-7i8, &IntFormat::new().zero_pad(3)).unwrap();
+/// b.append_ch(' ').unwrap();
+/// b.append_int_with(5u8, &IntFormat::new().plus_sign(true)).unwrap();
+/// b.append_ch(' ').unwrap();
+/// b.append_int_with(0b1011_0000u8, &IntFormat::new().radix(2).group('_', 4)).unwrap();
+/// b.append_ch(' ').unwrap();
+/// b.append_int_with(255u8, &IntFormat::new().radix(16).uppercase(true)).unwrap();
+/// assert_eq!("-007 +5 1011_0000 FF", b.as_str());
+/// assert!(b.append_int_with(1u8, &IntFormat::new().zero_pad(usize::MAX)).is_err());
+/// ```
+///
+#[derive(Debug, Clone, Copy)]
+pub struct IntFormat {
+    radix: u32,
+    width: usize,
+    plus: bool,
+    upper: bool,
+    group: Option<(char, usize)>,
+}
+
+impl Default for IntFormat {
+    fn default() -> Self { Self::new() }
+}
+
+impl IntFormat {
+    /// decimal, no padding, sign only when negative, no grouping
+    pub const fn new() -> Self {
+        Self { radix: 10, width: 0, plus: false, upper: false, group: None }
+    }
+
+    /// radix from 2 to 36, panic otherwise
+    pub const fn radix(mut self, radix: u32) -> Self {
+        assert!(2 <= radix && radix <= 36, "radix must be in 2..=36");
+        self.radix = radix;
+        self
+    }
+
+    /// minimum number of digits, filled with leading zeros (the sign is not counted)
+    pub const fn zero_pad(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// write `+` before positive numbers and zero
+    pub const fn plus_sign(mut self, yes: bool) -> Self {
+        self.plus = yes;
+        self
+    }
+
+    /// uppercase letter digits (radix above 10)
+    pub const fn uppercase(mut self, yes: bool) -> Self {
+        self.upper = yes;
+        self
+    }
+
+    /// insert `sep` between groups of `every` digits, counted from the right
+    pub const fn group(mut self, sep: char, every: usize) -> Self {
+        assert!(every > 0, "group size must not be 0");
+        self.group = Some((sep, every));
+        self
+    }
+
+    /// thousands separator, same as group(sep, 3)
+    pub const fn thousands(self, sep: char) -> Self {
+        self.group(sep, 3)
+    }
+}
+
+/// Float formatting options
+/// # example
+/// ```
+/// use cbfr::BFRDYN;
+/// use cbfr::num::FloatFormat;
+///
+/// let mut b: BFRDYN<32> = BFRDYN::new();
+/// b.append_float_with(-9876.5f32, &FloatFormat::new().thousands(' ')).unwrap();
+/// b.append_ch(' ').unwrap();
+/// b.append_float_with(0.125, &FloatFormat::new().precision(1).plus_sign(true)).unwrap();
+/// assert_eq!("-9 876.5 +0.1", b.as_str());
+/// assert!(b.append_float_with(1.5, &FloatFormat::new().precision(100_000)).is_err());
+/// assert!(b.append_float_with(1.5, &FloatFormat::new().precision(usize::MAX)).is_err());
+/// ```
+///
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FloatFormat {
+    precision: Option<usize>,
+    plus: bool,
+    thousands: Option<char>,
+}
+
+impl FloatFormat {
+    /// shortest representation that read back to the same value, no exponent
+    pub const fn new() -> Self {
+        Self { precision: None, plus: false, thousands: None }
+    }
+
+    /// fixed number of digits after the decimal point, rounded half to even
+    pub const fn precision(mut self, digits: usize) -> Self {
+        self.precision = Some(digits);
+        self
+    }
+
+    /// write `+` before positive numbers and zero
+    pub const fn plus_sign(mut self, yes: bool) -> Self {
+        self.plus = yes;
+        self
+    }
+
+    /// separator between groups of 3 digits of the integer part
+    pub const fn thousands(mut self, sep: char) -> Self {
+        self.thousands = Some(sep);
+        self
+    }
+}
+
+/// count the digits of the integer part of a formatted float
+struct IntDigits {
+    count: usize,
+    done: bool,
+}
+
+impl Write for IntDigits {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for b in s.bytes() {
+            if self.done { break; }
+            match b {
+                b'0'..=b'9' => self.count += 1,
+                b'-' | b'+' => {},
+                _ => self.done = true,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// append to a buffer with push_counted, inserting a separator in the integer part
+struct Grouped<'b, const CAPACITY: usize> {
+    buf: &'b mut BFRDYN<CAPACITY>,
+    total: usize,
+    sep: Option<char>,
+    digits: usize,
+    left: usize,
+}
+
+impl<const CAPACITY: usize> Write for Grouped<'_, CAPACITY> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            if c.is_ascii_digit() && self.left > 0 {
+                if let Some(sep) = self.sep {
+                    if self.left < self.digits && self.left % 3 == 0 {
+                        self.buf.push_char_counted(&mut self.total, sep);
+                    }
+                }
+                self.left -= 1;
+            } else if c != '-' && c != '+' {
+                self.left = 0;
+            }
+            self.buf.push_char_counted(&mut self.total, c);
+        }
+        Ok(())
+    }
+}
+
+/// parse an integer with an optional sign
+fn parse_int<T: Integer>(s: &str, radix: u32) -> Result<T, err::InvalidFormat> {
+    let bytes = s.as_bytes();
+    let (negative, start) = match bytes.first() {
+        Some(b'-') => (true, 1),
+        Some(b'+') => (false, 1),
+        _ => (false, 0),
+    };
+    if start == bytes.len() {
+        return Err(err::InvalidFormat::throw(start, "no digits"));
+    }
+    let max = T::max_magnitude(negative);
+    let mut magnitude = 0u128;
+    for (i, b) in bytes.iter().enumerate().skip(start) {
+        let digit = (*b as char).to_digit(radix)
+            .ok_or_else(|| err::InvalidFormat::throw(i, "invalid digit"))?;
+        magnitude = magnitude.checked_mul(radix as u128)
+            .and_then(|m| m.checked_add(digit as u128))
+            .filter(|m| *m <= max)
+            .ok_or_else(|| err::InvalidFormat::throw(i, "out of range"))?;
+    }
+    Ok(T::from_parts(negative, magnitude))
+}
+
+/// check the float syntax accepted by str::parse, to report the position of the first bad char
+fn check_float(s: &str) -> Result<(), err::InvalidFormat> {
+    let bytes = s.as_bytes();
+    let digits_from = |i: usize| bytes[i.min(bytes.len())..].iter().take_while(|b| b.is_ascii_digit()).count();
+    let fail = |i: usize| {
+        let reason = if i == bytes.len() { "no digits" } else { "invalid digit" };
+        Err(err::InvalidFormat::throw(i, reason))
+    };
+    let mut i = matches!(bytes.first(), Some(b'+' | b'-')) as usize;
+    let word = &s[i..];
+    if ["inf", "infinity", "nan"].iter().any(|w| word.eq_ignore_ascii_case(w)) {
+        return Ok(());
+    }
+    let int = digits_from(i);
+    i += int;
+    let mut frac = 0;
+    if bytes.get(i) == Some(&b'.') {
+        frac = digits_from(i + 1);
+        i += 1 + frac;
+    }
+    if int + frac == 0 {
+        return fail(i);
+    }
+    if matches!(bytes.get(i), Some(b'e' | b'E')) {
+        i += 1;
+        if matches!(bytes.get(i), Some(b'+' | b'-')) { i += 1; }
+        let exp = digits_from(i);
+        if exp == 0 {
+            return fail(i);
+        }
+        i += exp;
+    }
+    if i < bytes.len() {
+        return fail(i);
+    }
+    Ok(())
+}
+
+impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
+    /// append an integer in decimal
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    ///
+    /// let mut b: BFRDYN<8> = "n=".into();
+    /// b.append_int(-128i8).unwrap();
+    /// assert_eq!("n=-128", b.as_str());
+    /// assert!(b.append_int(u64::MAX).is_err());
+    /// assert_eq!("n=-128", b.as_str());
+    /// ```
+    ///
+    pub fn append_int<T: Integer>(&mut self, n: T) -> NecResult {
+        self.append_int_with(n, &IntFormat::new())
+    }
+
+    /// append an integer with formatting options, see [IntFormat]
+    pub fn append_int_with<T: Integer>(&mut self, n: T, opts: &IntFormat) -> NecResult {
+        let (negative, mut magnitude) = n.to_parts();
+        // enough for u128 in base 2
+        let mut digits = [0u8; 128];
+        let mut start = digits.len();
+        let letters = if opts.upper { b'A' } else { b'a' };
+        let radix = opts.radix as u128;
+        loop {
+            let d = (magnitude % radix) as u8;
+            magnitude /= radix;
+            start -= 1;
+            digits[start] = if d < 10 { b'0' + d } else { letters + d - 10 };
+            if magnitude == 0 { break; }
+        }
+        let zeros = opts.width.saturating_sub(digits.len() - start);
+        let count = zeros + (digits.len() - start);
+        // a huge zero_pad must fail before writing its zeros one by one
+        let sign = usize::from(negative || opts.plus);
+        let seps = opts.group
+            .map_or(Some(0), |(sep, every)| ((count - 1) / every).checked_mul(sep.len_utf8()));
+        let needed = seps
+            .and_then(|n| n.checked_add(count))
+            .and_then(|n| n.checked_add(sign))
+            .and_then(|n| n.checked_add(self.len))
+            .ok_or_else(|| err::NotEnoughCapacity::throw(CAPACITY, usize::MAX))?;
+        if needed > CAPACITY {
+            return Err(err::NotEnoughCapacity::throw(CAPACITY, needed));
+        }
+
+        let old = self.len;
+        let mut total = self.len;
+        if negative {
+            self.push_counted(&mut total, b"-");
+        } else if opts.plus {
+            self.push_counted(&mut total, b"+");
+        }
+        let digit_iter = std::iter::repeat_n(b'0', zeros).chain(digits[start..].iter().copied());
+        for (k, d) in digit_iter.enumerate() {
+            if let Some((sep, every)) = opts.group {
+                if k > 0 && (count - k) % every == 0 {
+                    self.push_char_counted(&mut total, sep);
+                }
+            }
+            self.push_counted(&mut total, &[d]);
+        }
+        if total > CAPACITY {
+            self.remove_bytes(old, self.len);
+            return Err(err::NotEnoughCapacity::throw(CAPACITY, total));
+        }
+        Ok(())
+    }
+
+    /// append a float in its shortest roundtrip form (`0.1`, `1e21` is written in full)
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    ///
+    /// let mut b: BFRDYN<32> = BFRDYN::new();
+    /// b.append_float(0.1 + 0.2).unwrap();
+    /// b.append_ch(' ').unwrap();
+    /// b.append_float(0.1f32).unwrap();
+    /// b.append_ch(' ').unwrap();
+    /// b.append_float(f64::NEG_INFINITY).unwrap();
+    /// assert_eq!("0.30000000000000004 0.1 -inf", b.as_str());
+    /// ```
+    ///
+    pub fn append_float<F: Float>(&mut self, x: F) -> NecResult {
+        self.append_float_with(x, &FloatFormat::new())
+    }
+
+    /// append a float with formatting options, see [FloatFormat]
+    pub fn append_float_with<F: Float>(&mut self, x: F, opts: &FloatFormat) -> NecResult {
+        // a huge precision must fail before fmt panics or writes its zeros one by one
+        if let Some(p) = opts.precision {
+            let needed = p.checked_add(1)
+                .and_then(|n| n.checked_add(self.len))
+                .ok_or_else(|| err::NotEnoughCapacity::throw(CAPACITY, usize::MAX))?;
+            if p > u16::MAX as usize || needed > CAPACITY {
+                return Err(err::NotEnoughCapacity::throw(CAPACITY, needed));
+            }
+        }
+        let write = |out: &mut dyn Write| match (opts.precision, opts.plus) {
+            (None, false) => write!(out, "{}", x),
+            (None, true) => write!(out, "{:+}", x),
+            (Some(p), false) => write!(out, "{:.*}", p, x),
+            (Some(p), true) => write!(out, "{:+.*}", p, x),
+        };
+        let mut int = IntDigits { count: 0, done: false };
+        if opts.thousands.is_some() {
+            let _ = write(&mut int);
+        }
+        let old = self.len;
+        let mut sink = Grouped { total: self.len, buf: self, sep: opts.thousands, digits: int.count, left: int.count };
+        // the sink never fail, overflow is counted in total
+        let _ = write(&mut sink);
+        let total = sink.total;
+        if total > CAPACITY {
+            self.remove_bytes(old, self.len);
+            return Err(err::NotEnoughCapacity::throw(CAPACITY, total));
+        }
+        Ok(())
+    }
+
+    /// parse the buffer as a decimal integer with an optional sign
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    ///
+    /// let b: BFRDYN<16> = "300".into();
+    /// assert_eq!(300u16, b.parse_int().unwrap());
+    /// let e = b.parse_int::<u8>().unwrap_err();
+    /// assert_eq!((2, "out of range"), (e.index(), e.reason()));
+    ///
+    /// let b: BFRDYN<16> = "12a4".into();
+    /// assert_eq!(2, b.parse_int::<i64>().unwrap_err().index());
+    /// ```
+    ///
+    pub fn parse_int<T: Integer>(&self) -> Result<T, err::InvalidFormat> {
+        parse_int(self.as_str(), 10)
+    }
+
+    /// parse the buffer as an integer in `radix` (2 to 36, panic otherwise)
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    ///
+    /// let b: BFRDYN<16> = "-ff".into();
+    /// assert_eq!(-255i32, b.parse_int_radix(16).unwrap());
+    /// ```
+    ///
+    pub fn parse_int_radix<T: Integer>(&self, radix: u32) -> Result<T, err::InvalidFormat> {
+        assert!((2..=36).contains(&radix), "radix must be in 2..=36");
+        parse_int(self.as_str(), radix)
+    }
+
+    /// parse the buffer as a f64 (same syntax as str::parse: `1.5e-3`, `.5`, `inf`, `NaN` ...)
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    ///
+    /// let b: BFRDYN<16> = "-2.5e3".into();
+    /// assert_eq!(-2500.0, b.parse_float().unwrap());
+    ///
+    /// let b: BFRDYN<16> = "1.5e+".into();
+    /// let e = b.parse_float().unwrap_err();
+    /// assert_eq!((5, "no digits"), (e.index(), e.reason()));
+    /// ```
+    ///
+    pub fn parse_float(&self) -> Result<f64, err::InvalidFormat> {
+        f64::parse_str(self.as_str())
+    }
+
+    /// parse the buffer as any integer or float primitive
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    ///
+    /// let b: BFRDYN<16> = "0.25".into();
+    /// assert_eq!(0.25f32, b.parse().unwrap());
+    /// assert_eq!(1, b.parse::<i32>().unwrap_err().index());
+    /// ```
+    ///
+    pub fn parse<T: Parse>(&self) -> Result<T, err::InvalidFormat> {
+        T::parse_str(self.as_str())
+    }
+}
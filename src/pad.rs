@@ -0,0 +1,209 @@
+//! Padding, centering and truncation of a [BFRDYN] to a fixed width,
+//! measured in chars or in display columns (see [Width]).
+//! Every function return NotEnoughCapacity instead of panicking when the result
+//! doesn't fit, the buffer is left unchanged on error. Padding in display columns
+//! with a fill char that has no width (like U+0301) can never reach the width and panic.
+//! # example
+//! ```
+//! use cbfr::BFRDYN;
+//! use cbfr::pad::Align;
+//! use cbfr::width::Width;
+//!
+//! let mut name: BFRDYN<32> = "Kim".into();
+//! name.pad_right(6, '.').unwrap();
+//! assert_eq!("Kim...", name.as_str());
+//!
+//! let mut city: BFRDYN<32> = "東京".into();
+//! city.fit(Width::Columns(6), Align::Right).unwrap();
+//! assert_eq!("  東京", city.as_str());
+//!
+//! let mut long: BFRDYN<32> = "Constantinople".into();
+//! long.fit(8, Align::Left).unwrap();
+//! assert_eq!("Constan…", long.as_str());
+//! ```
+
+use crate::cb::{BFRDYN, NecResult};
+use crate::errors as err;
+use crate::width::Width;
+
+/// alignment of a text in a wider column
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Align {
+    #[default]
+    Left,
+    Right,
+    Center,
+}
+
+impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
+    /// number of fill chars needed to reach width, never going over it
+    fn fill_count(&self, width: Width, fill: char) -> usize {
+        let fill_width = width.measure_char(fill);
+        assert!(fill_width > 0, "fill char must have a display width");
+        let missing = width.value().saturating_sub(width.measure(self.as_str()));
+        missing / fill_width
+    }
+
+    /// add `left` fill chars before and `right` after the text in one copy
+    fn pad(&mut self, left: usize, right: usize, fill: char) -> NecResult {
+        let mut tmp = [0u8; 4];
+        let fill = fill.encode_utf8(&mut tmp).as_bytes();
+        // a huge width must not overflow past the capacity check
+        let total = left.checked_add(right)
+            .and_then(|n| n.checked_mul(fill.len()))
+            .and_then(|n| n.checked_add(self.len))
+            .ok_or_else(|| err::NotEnoughCapacity::throw(CAPACITY, usize::MAX))?;
+        if total > CAPACITY {
+            return Err(err::NotEnoughCapacity::throw(CAPACITY, total));
+        }
+        let shift = left * fill.len();
+        self.arr.copy_within(0..self.len, shift);
+        for chunk in self.arr[..shift].chunks_exact_mut(fill.len()) {
+            chunk.copy_from_slice(fill);
+        }
+        for chunk in self.arr[shift + self.len..total].chunks_exact_mut(fill.len()) {
+            chunk.copy_from_slice(fill);
+        }
+        self.len = total;
+        Ok(())
+    }
+
+    /// display columns of the buffer, East Asian wide chars count as 2
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    ///
+    /// let b: BFRDYN<32> = "한국어 ok".into();
+    /// assert_eq!(6, b.as_str().chars().count());
+    /// assert_eq!(9, b.display_width());
+    /// ```
+    ///
+    pub fn display_width(&self) -> usize {
+        crate::width::str_width(self.as_str())
+    }
+
+    /// insert fill chars on the left until the text is `width` wide.
+    /// Width is in chars for a `usize` or `Width::Chars`, in display columns for `Width::Columns`
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    ///
+    /// let mut b: BFRDYN<8> = "42".into();
+    /// b.pad_left(5, '0').unwrap();
+    /// assert_eq!("00042", b.as_str());
+    /// assert!(b.pad_left(9, '0').is_err());
+    /// assert!(b.pad_left(usize::MAX, 'é').is_err());
+    /// assert_eq!("00042", b.as_str());
+    /// ```
+    ///
+    pub fn pad_left(&mut self, width: impl Into<Width>, fill: char) -> NecResult {
+        let n = self.fill_count(width.into(), fill);
+        self.pad(n, 0, fill)
+    }
+
+    /// append fill chars until the text is `width` wide
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    /// use cbfr::width::Width;
+    ///
+    /// let mut b: BFRDYN<16> = "字".into();
+    /// b.pad_right(Width::Columns(4), '-').unwrap();
+    /// assert_eq!("字--", b.as_str());
+    /// ```
+    /// # Panic
+    /// ```should_panic
+    /// use cbfr::BFRDYN;
+    /// use cbfr::width::Width;
+    ///
+    /// let mut b: BFRDYN<16> = "e".into();
+    /// // a combining accent take no column, padding with it would never reach the width
+    /// let _ = b.pad_right(Width::Columns(4), '\u{301}');
+    /// ```
+    ///
+    pub fn pad_right(&mut self, width: impl Into<Width>, fill: char) -> NecResult {
+        let n = self.fill_count(width.into(), fill);
+        self.pad(0, n, fill)
+    }
+
+    /// pad both sides so the text is centered in `width`, the extra fill char go to the right
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    ///
+    /// let mut b: BFRDYN<16> = "menu".into();
+    /// b.center(9, '*').unwrap();
+    /// assert_eq!("**menu***", b.as_str());
+    /// assert!(b.center(usize::MAX, '─').is_err());
+    /// assert!(b.pad_right(usize::MAX, '─').is_err());
+    /// ```
+    ///
+    pub fn center(&mut self, width: impl Into<Width>, fill: char) -> NecResult {
+        let n = self.fill_count(width.into(), fill);
+        self.pad(n / 2, n - n / 2, fill)
+    }
+
+    /// cut the text so it fit in `width` with the ellipsis appended,
+    /// a text that already fit is unchanged. If the ellipsis itself is wider than `width`
+    /// the text is cut without it
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    ///
+    /// let mut b: BFRDYN<16> = "hello world".into();
+    /// b.truncate_with_ellipsis(8, "...").unwrap();
+    /// assert_eq!("hello...", b.as_str());
+    ///
+    /// // "…" take 3 bytes for the 2 it replace
+    /// let mut full: BFRDYN<4> = "abcd".into();
+    /// assert!(full.truncate_with_ellipsis(3, "…").is_err());
+    /// assert_eq!("abcd", full.as_str());
+    /// ```
+    ///
+    pub fn truncate_with_ellipsis(&mut self, width: impl Into<Width>, ellipsis: &str) -> NecResult {
+        let width = width.into();
+        if width.measure(self.as_str()) <= width.value() { return Ok(()); }
+        let ellipsis = if width.measure(ellipsis) <= width.value() { ellipsis } else { "" };
+        let budget = width.value() - width.measure(ellipsis);
+        let mut used = 0;
+        let mut cut = 0;
+        for (i, c) in self.as_str().char_indices() {
+            used += width.measure_char(c);
+            if used > budget { break; }
+            cut = i + c.len_utf8();
+        }
+        let total = cut + ellipsis.len();
+        if total > CAPACITY {
+            return Err(err::NotEnoughCapacity::throw(CAPACITY, total));
+        }
+        self.remove_bytes(cut, self.len);
+        self.insert_bytes(cut, ellipsis.as_bytes());
+        Ok(())
+    }
+
+    /// make the text exactly `width` wide: truncate it with "…" when too long,
+    /// pad it with spaces following `align` when too short.
+    /// In display columns a wide char may leave the result one column short
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    /// use cbfr::pad::Align;
+    ///
+    /// let mut b: BFRDYN<16> = "ok".into();
+    /// b.fit(6, Align::Center).unwrap();
+    /// assert_eq!("  ok  ", b.as_str());
+    /// ```
+    ///
+    pub fn fit(&mut self, width: impl Into<Width>, align: Align) -> NecResult {
+        let width = width.into();
+        let mut out = self.clone();
+        out.truncate_with_ellipsis(width, "…")?;
+        match align {
+            Align::Left => out.pad_right(width, ' ')?,
+            Align::Right => out.pad_left(width, ' ')?,
+            Align::Center => out.center(width, ' ')?,
+        }
+        *self = out;
+        Ok(())
+    }
+}
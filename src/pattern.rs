@@ -0,0 +1,36 @@
+//! Char patterns accepted by the trimming functions of [BFRDYN](crate::BFRDYN)
+//! (`trim_matches`, `trim_start_matches`, `trim_end_matches`):
+//! a char, an array or slice of chars (any of them) or a `FnMut(char) -> bool`.
+//! # example
+//! ```
+//! use cbfr::BFRDYN;
+//!
+//! let mut b: BFRDYN<32> = "--=title=--".into();
+//! b.trim_matches(['-', '=']);
+//! assert_eq!("title", b.as_str());
+//!
+//! b.trim_end_matches(|c: char| c != 't');
+//! assert_eq!("tit", b.as_str());
+//! ```
+
+/// something that can test a char
+pub trait CharPattern {
+    /// true if the char match the pattern
+    fn matches(&mut self, c: char) -> bool;
+}
+
+impl CharPattern for char {
+    fn matches(&mut self, c: char) -> bool { *self == c }
+}
+
+impl<const N: usize> CharPattern for [char; N] {
+    fn matches(&mut self, c: char) -> bool { self.contains(&c) }
+}
+
+impl CharPattern for &[char] {
+    fn matches(&mut self, c: char) -> bool { self.contains(&c) }
+}
+
+impl<F: FnMut(char) -> bool> CharPattern for F {
+    fn matches(&mut self, c: char) -> bool { self(c) }
+}
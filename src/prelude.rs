@@ -4,7 +4,71 @@ pub use super::cb:: {
     IidxResult
 };
 
+pub use super::ring:: {
+    BFRRING,
+    RingMode
+};
+
+pub use super::gap::BFRGAP;
+
+pub use super::undo::BFRUNDO;
+
+pub use super::table::BFRTABLE;
+
+pub use super::binary::BFRBIN;
+
+pub use super::intern:: {
+    Interner,
+    Symbol
+};
+
+pub use super::map:: {
+    BFRMAP,
+    Entry
+};
+
+pub use super::trie::BFRTRIE;
+
+pub use super::lru:: {
+    LruCache,
+    LruBfr
+};
+
+pub use super::search::Finder;
+
+pub use super::aho:: {
+    AhoCorasick,
+    MatchKind
+};
+
+pub use super::regex::Regex;
+
+pub use super::glob::Glob;
+
+pub use super::pattern::CharPattern;
+
+pub use super::width::Width;
+
+pub use super::pad::Align;
+
+pub use super::wrap::Wrap;
+
+pub use super::url::QueryBuilder;
+
+pub use super::codec::Encoding;
+
+pub use super::num:: {
+    IntFormat,
+    FloatFormat
+};
+
+pub use super::fuzzy:: {
+    Score,
+    TopK
+};
+
 pub use super::errors:: {
     InvalidIndex,
-    NotEnoughCapacity
+    NotEnoughCapacity,
+    InvalidFormat
 };
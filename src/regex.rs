@@ -0,0 +1,631 @@
+use std::ops::Range;
+
+use crate::errors as err;
+
+/// capture slot not set
+const UNSET: u32 = u32::MAX;
+
+/// one instruction of the pike vm program
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Inst {
+    Char(char),
+    /// any char but '\n'
+    Any,
+    /// char in (or not in) ranges[start..start + len]
+    Class { start: usize, len: usize, negated: bool },
+    /// try both, the first one has priority
+    Split(usize, usize),
+    Jmp(usize),
+    /// alternative jump whose target is not known yet, link to the previous one
+    Pending(Option<usize>),
+    Save(usize),
+    Bol,
+    Eol,
+    /// \b when true, \B when false
+    WordBoundary(bool),
+    Match,
+}
+
+impl Inst {
+    /// apply `f` to every jump target
+    fn map_targets(self, f: impl Fn(usize) -> usize) -> Self {
+        match self {
+            Inst::Split(a, b) => Inst::Split(f(a), f(b)),
+            Inst::Jmp(t) => Inst::Jmp(f(t)),
+            other => other,
+        }
+    }
+}
+
+const DIGIT: &[(char, char)] = &[('0', '9')];
+const WORD: &[(char, char)] = &[('0', '9'), ('A', 'Z'), ('_', '_'), ('a', 'z')];
+const SPACE: &[(char, char)] = &[('\t', '\r'), (' ', ' ')];
+
+fn is_word(c: Option<char>) -> bool {
+    c.is_some_and(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Regex is a small regular expression compiled into a fixed size program
+/// run by a Pike VM, it never allocate.
+/// - INSTS is the program capacity, it also bound the ranges of character classes
+/// - CAPS is the number of capture slots, group 0 (the whole match) included,
+///   so it must be at least 1
+///
+/// Supported syntax: literals, `.`, classes `[a-z]` `[^0-9]`, `\d \w \s \D \W \S`,
+/// anchors `^ $ \b \B`, alternation `|`, groups `( )` and `(?: )`,
+/// repetition `* + ? {m} {m,} {m,n}` (add `?` for the lazy version).
+/// Matching is leftmost first (like Perl), positions are byte offsets.
+/// Like other Pike VMs, a group that can match empty inside a loop may report
+/// a different (unset) capture than a backtracking engine would.
+/// # example
+/// ```
+/// use cbfr::regex::Regex;
+/// use cbfr::BFRDYN;
+///
+/// let date: Regex = Regex::new(r"(\d{4})-(\d{2})-(\d{2})").unwrap();
+/// let text: BFRDYN<64> = "released on 2024-03-15.".into();
+///
+/// let caps = date.captures(&text).unwrap();
+/// assert_eq!(Some(12..22), caps.get(0));
+/// assert_eq!("03", &text.as_str()[caps.get(2).unwrap()]);
+///
+/// // a starred group that can match empty keeps the leftmost first match range
+/// let re: Regex = Regex::new(r"(?:c*[ab]??)*.{1,2}").unwrap();
+/// assert_eq!(Some(0..2), re.find("a cbb"));
+/// let re: Regex<128, 2> = Regex::new(r"(\b[ab]?|[ab]{0,}.{1,2}){0,}").unwrap();
+/// assert_eq!(Some(0..0), re.find("c c abc"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Regex<const INSTS: usize = 128, const CAPS: usize = 4> {
+    prog: [Inst; INSTS],
+    len: usize,
+    ranges: [(char, char); INSTS],
+    nranges: usize,
+    groups: usize,
+}
+
+/// Capture groups of a match, see [Regex::captures]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Captures<const CAPS: usize> {
+    slots: [[u32; 2]; CAPS],
+    groups: usize,
+}
+
+impl<const CAPS: usize> Captures<CAPS> {
+    /// byte range of group i, None if the group didn't take part in the match
+    pub fn get(&self, i: usize) -> Option<Range<usize>> {
+        let [start, end] = *self.slots.get(i)?;
+        if start == UNSET || end == UNSET { return None; }
+        Some(start as usize..end as usize)
+    }
+
+    /// number of groups, group 0 included
+    pub const fn groups(&self) -> usize { self.groups }
+}
+
+/// parser that emit the program straight into the regex
+struct Compiler<'p, 'r, const INSTS: usize, const CAPS: usize> {
+    pattern: &'p str,
+    pos: usize,
+    re: &'r mut Regex<INSTS, CAPS>,
+}
+
+impl<const INSTS: usize, const CAPS: usize> Compiler<'_, '_, INSTS, CAPS> {
+    fn peek(&self) -> Option<char> { self.pattern[self.pos..].chars().next() }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) { self.pos += 1; true } else { false }
+    }
+
+    fn error(&self, reason: &'static str) -> err::InvalidFormat {
+        err::InvalidFormat::throw(self.pos, reason)
+    }
+
+    fn full(&self) -> err::InvalidFormat {
+        self.error("pattern too large for program capacity")
+    }
+
+    fn emit(&mut self, inst: Inst) -> Result<usize, err::InvalidFormat> {
+        if self.re.len == INSTS { return Err(self.full()); }
+        self.re.prog[self.re.len] = inst;
+        self.re.len += 1;
+        Ok(self.re.len - 1)
+    }
+
+    /// insert an instruction at `at`, the code after it move by one
+    fn insert(&mut self, at: usize, inst: Inst) -> Result<(), err::InvalidFormat> {
+        if self.re.len == INSTS { return Err(self.full()); }
+        let len = self.re.len;
+        self.re.prog.copy_within(at..len, at + 1);
+        for i in 0..=len {
+            if i == at { continue; }
+            // code before `at` keep pointing at `at` (now the new instruction)
+            let moved = |t: usize| if t > at || (i > at && t == at) { t + 1 } else { t };
+            self.re.prog[i] = self.re.prog[i].map_targets(moved);
+        }
+        self.re.prog[at] = inst;
+        self.re.len += 1;
+        Ok(())
+    }
+
+    /// append a copy of the code in [src, src + len)
+    fn copy(&mut self, src: usize, len: usize) -> Result<(), err::InvalidFormat> {
+        let dst = self.re.len;
+        for k in 0..len {
+            let inst = self.re.prog[src + k].map_targets(|t| {
+                if (src..=src + len).contains(&t) { t + dst - src } else { t }
+            });
+            self.emit(inst)?;
+        }
+        Ok(())
+    }
+
+    fn add_ranges(&mut self, ranges: &[(char, char)]) -> Result<(), err::InvalidFormat> {
+        for r in ranges {
+            if self.re.nranges == INSTS { return Err(self.full()); }
+            self.re.ranges[self.re.nranges] = *r;
+            self.re.nranges += 1;
+        }
+        Ok(())
+    }
+
+    fn class_of(&mut self, ranges: &[(char, char)], negated: bool) -> Result<(), err::InvalidFormat> {
+        let start = self.re.nranges;
+        self.add_ranges(ranges)?;
+        self.emit(Inst::Class { start, len: ranges.len(), negated })?;
+        Ok(())
+    }
+
+    /// alternation := concat ('|' concat)*
+    fn alternation(&mut self) -> Result<(), err::InvalidFormat> {
+        let mut alt_start = self.re.len;
+        let mut pending = None;
+        self.concat()?;
+        while self.eat('|') {
+            self.insert(alt_start, Inst::Split(alt_start + 1, 0))?;
+            pending = Some(self.emit(Inst::Pending(pending))?);
+            let next = self.re.len;
+            self.re.prog[alt_start] = Inst::Split(alt_start + 1, next);
+            alt_start = next;
+            self.concat()?;
+        }
+        let end = self.re.len;
+        while let Some(at) = pending {
+            let Inst::Pending(prev) = self.re.prog[at] else { unreachable!("pending chain") };
+            self.re.prog[at] = Inst::Jmp(end);
+            pending = prev;
+        }
+        Ok(())
+    }
+
+    /// concat := repeat*
+    fn concat(&mut self) -> Result<(), err::InvalidFormat> {
+        while !matches!(self.peek(), None | Some('|') | Some(')')) {
+            let start = self.re.len;
+            self.atom()?;
+            self.repeat(start)?;
+        }
+        Ok(())
+    }
+
+    /// atom := group | class | escape | '.' | '^' | '$' | char
+    fn atom(&mut self) -> Result<(), err::InvalidFormat> {
+        let at = self.pos;
+        match self.bump() {
+            Some('(') => {
+                let group = if self.pattern[self.pos..].starts_with("?:") {
+                    self.pos += 2;
+                    None
+                } else {
+                    if self.re.groups == CAPS {
+                        self.pos = at;
+                        return Err(self.error("too many capture groups"));
+                    }
+                    self.re.groups += 1;
+                    Some(self.re.groups - 1)
+                };
+                if let Some(g) = group { self.emit(Inst::Save(2 * g))?; }
+                self.alternation()?;
+                if !self.eat(')') {
+                    self.pos = at;
+                    return Err(self.error("unclosed group"));
+                }
+                if let Some(g) = group { self.emit(Inst::Save(2 * g + 1))?; }
+            },
+            Some(')') => { self.pos = at; return Err(self.error("unopened group")); },
+            Some('*' | '+' | '?') => { self.pos = at; return Err(self.error("nothing to repeat")); },
+            Some('[') => self.class(at)?,
+            Some('.') => { self.emit(Inst::Any)?; },
+            Some('^') => { self.emit(Inst::Bol)?; },
+            Some('$') => { self.emit(Inst::Eol)?; },
+            Some('\\') => self.escape(at)?,
+            Some(c) => { self.emit(Inst::Char(c))?; },
+            None => return Err(self.error("unexpected end of pattern")),
+        }
+        Ok(())
+    }
+
+    fn escape(&mut self, at: usize) -> Result<(), err::InvalidFormat> {
+        let inst = match self.bump() {
+            Some('d') => return self.class_of(DIGIT, false),
+            Some('D') => return self.class_of(DIGIT, true),
+            Some('w') => return self.class_of(WORD, false),
+            Some('W') => return self.class_of(WORD, true),
+            Some('s') => return self.class_of(SPACE, false),
+            Some('S') => return self.class_of(SPACE, true),
+            Some('b') => Inst::WordBoundary(true),
+            Some('B') => Inst::WordBoundary(false),
+            Some(c) => match Self::escaped_char(c) {
+                Some(c) => Inst::Char(c),
+                None => { self.pos = at; return Err(self.error("invalid escape")); },
+            },
+            None => { self.pos = at; return Err(self.error("unfinished escape")); },
+        };
+        self.emit(inst)?;
+        Ok(())
+    }
+
+    /// char of a single char escape
+    fn escaped_char(c: char) -> Option<char> {
+        match c {
+            'n' => Some('\n'),
+            't' => Some('\t'),
+            'r' => Some('\r'),
+            '0' => Some('\0'),
+            c if c.is_ascii_punctuation() || c == ' ' => Some(c),
+            _ => None,
+        }
+    }
+
+    /// class := '[' '^'? item+ ']', `at` is the position of '['
+    fn class(&mut self, at: usize) -> Result<(), err::InvalidFormat> {
+        let negated = self.eat('^');
+        let start = self.re.nranges;
+        let mut first = true;
+        loop {
+            let item = self.pos;
+            let lo = match self.bump() {
+                None => { self.pos = at; return Err(self.error("unclosed class")); },
+                Some(']') if !first => break,
+                Some('\\') => match self.bump() {
+                    Some('d') => { self.add_ranges(DIGIT)?; first = false; continue; },
+                    Some('w') => { self.add_ranges(WORD)?; first = false; continue; },
+                    Some('s') => { self.add_ranges(SPACE)?; first = false; continue; },
+                    Some(c) => match Self::escaped_char(c) {
+                        Some(c) => c,
+                        None => { self.pos = item; return Err(self.error("invalid escape in class")); },
+                    },
+                    None => { self.pos = at; return Err(self.error("unclosed class")); },
+                },
+                Some(c) => c,
+            };
+            first = false;
+            let mut hi = lo;
+            if self.peek() == Some('-') && !self.pattern[self.pos + 1..].starts_with(']') {
+                self.pos += 1;
+                hi = match self.bump() {
+                    Some('\\') => self.bump().and_then(Self::escaped_char),
+                    other => other,
+                }.ok_or_else(|| self.error("invalid class range"))?;
+                if hi < lo {
+                    self.pos = item;
+                    return Err(self.error("invalid class range"));
+                }
+            }
+            self.add_ranges(&[(lo, hi)])?;
+        }
+        let len = self.re.nranges - start;
+        self.emit(Inst::Class { start, len, negated })?;
+        Ok(())
+    }
+
+    fn number(&mut self) -> Option<usize> {
+        let digits = self.pattern[self.pos..].bytes().take_while(u8::is_ascii_digit).count();
+        let n = self.pattern[self.pos..self.pos + digits].parse().ok()?;
+        self.pos += digits;
+        Some(n)
+    }
+
+    /// parse `{m}`, `{m,}` or `{m,n}`, restore position and return None if it isn't one
+    fn bounds(&mut self) -> Option<(usize, Option<usize>)> {
+        let at = self.pos;
+        let parsed = (|| {
+            if !self.eat('{') { return None; }
+            let min = self.number()?;
+            let max = if self.eat(',') {
+                if self.peek() == Some('}') { None } else { Some(self.number()?) }
+            } else {
+                Some(min)
+            };
+            if !self.eat('}') { return None; }
+            Some((min, max))
+        })();
+        if parsed.is_none() { self.pos = at; }
+        parsed
+    }
+
+    /// check if the code in [start, end) can match without consuming a char
+    fn nullable(&self, start: usize, end: usize) -> bool {
+        fn walk(prog: &[Inst], seen: &mut [bool], start: usize, end: usize, pc: usize) -> bool {
+            if pc == end { return true; }
+            if pc < start || pc > end || seen[pc] { return false; }
+            seen[pc] = true;
+            match prog[pc] {
+                Inst::Split(a, b) => walk(prog, seen, start, end, a) || walk(prog, seen, start, end, b),
+                Inst::Jmp(t) => walk(prog, seen, start, end, t),
+                Inst::Save(_) | Inst::Bol | Inst::Eol | Inst::WordBoundary(_) => walk(prog, seen, start, end, pc + 1),
+                _ => false,
+            }
+        }
+        walk(&self.re.prog, &mut [false; INSTS], start, end, start)
+    }
+
+    /// quantifier applied to the code emitted from `start`
+    fn repeat(&mut self, start: usize) -> Result<(), err::InvalidFormat> {
+        let at = self.pos;
+        let (min, max) = match self.peek() {
+            Some('*') => { self.pos += 1; (0, None) },
+            Some('+') => { self.pos += 1; (1, None) },
+            Some('?') => { self.pos += 1; (0, Some(1)) },
+            Some('{') => match self.bounds() {
+                Some(b) => b,
+                None => return Ok(()),
+            },
+            _ => return Ok(()),
+        };
+        let lazy = self.eat('?');
+        if max.is_some_and(|max| max < min) {
+            self.pos = at;
+            return Err(self.error("invalid repetition range"));
+        }
+        // every copy takes at least one instruction, so this can never fit
+        if min > INSTS || max.is_some_and(|max| max > INSTS) {
+            self.pos = at;
+            return Err(self.full());
+        }
+        if self.re.len == start {
+            self.pos = at;
+            return Err(self.error("nothing to repeat"));
+        }
+        let len = self.re.len - start;
+        // branch order of a split: (continue the loop, leave it)
+        let split = |stay: usize, leave: usize| {
+            if lazy { Inst::Split(leave, stay) } else { Inst::Split(stay, leave) }
+        };
+        if max == Some(0) {
+            self.re.len = start;
+            return Ok(());
+        }
+        // mandatory copies, the original is the first one
+        for _ in 1..min {
+            self.copy(start, len)?;
+        }
+        match max {
+            None if min == 0 && self.nullable(start, self.re.len) => {
+                // e* with e matching empty  =>  (?:e+)?  =>  split(e, end); e; split(e, end)
+                // looping back to the first split would kill a thread whose e matched
+                // empty, and its way out would lose its priority to the skip branch
+                let end = self.re.len + 2;
+                self.insert(start, split(start + 1, end))?;
+                self.emit(split(start + 1, end))?;
+            },
+            None if min == 0 => {
+                // e*  =>  split(e, end); e; jmp split
+                let end = self.re.len + 2;
+                self.insert(start, split(start + 1, end))?;
+                self.emit(Inst::Jmp(start))?;
+            },
+            None => {
+                // e+ on the last copy  =>  e; split(e, end)
+                let last = self.re.len - len;
+                let end = self.re.len + 1;
+                self.emit(split(last, end))?;
+            },
+            Some(max) => {
+                // optional copies  =>  split(e, end); e; split(e, end); e ...
+                let optional = max - min;
+                let mut src = start;
+                let base = if min == 0 { start } else { self.re.len };
+                let end = match optional.checked_mul(len + 1).and_then(|n| n.checked_add(base)) {
+                    Some(end) if end <= INSTS => end,
+                    _ => return Err(self.full()),
+                };
+                let mut remaining = optional;
+                if min == 0 {
+                    self.insert(start, split(start + 1, end))?;
+                    src = start + 1;
+                    remaining -= 1;
+                }
+                for _ in 0..remaining {
+                    self.emit(split(self.re.len + 1, end))?;
+                    self.copy(src, len)?;
+                }
+            },
+        }
+        Ok(())
+    }
+}
+
+/// list of threads, a sparse set of program counters with their captures
+struct Threads<const INSTS: usize, const CAPS: usize> {
+    pcs: [usize; INSTS],
+    index: [usize; INSTS],
+    caps: [[[u32; 2]; CAPS]; INSTS],
+    len: usize,
+}
+
+impl<const INSTS: usize, const CAPS: usize> Threads<INSTS, CAPS> {
+    fn new() -> Self {
+        Self { pcs: [0; INSTS], index: [0; INSTS], caps: [[[UNSET; 2]; CAPS]; INSTS], len: 0 }
+    }
+
+    fn contains(&self, pc: usize) -> bool {
+        let i = self.index[pc];
+        i < self.len && self.pcs[i] == pc
+    }
+
+    fn push(&mut self, pc: usize, caps: [[u32; 2]; CAPS]) {
+        self.index[pc] = self.len;
+        self.pcs[self.len] = pc;
+        self.caps[self.len] = caps;
+        self.len += 1;
+    }
+}
+
+impl<const INSTS: usize, const CAPS: usize> Regex<INSTS, CAPS> {
+    /// compile a pattern. Return InvalidFormat with the position of the problem
+    /// for bad syntax, too many groups, no slot for group 0 (CAPS is 0)
+    /// or when the program doesn't fit in INSTS
+    /// # example
+    /// ```
+    /// use cbfr::regex::Regex;
+    ///
+    /// assert!(Regex::<128, 4>::new("a(b").is_err());
+    /// let err = Regex::<8, 4>::new("[a-z]{10}").unwrap_err();
+    /// assert_eq!("pattern too large for program capacity", err.reason());
+    /// let err = Regex::<64, 2>::new("a{0,9999999999999999999}").unwrap_err();
+    /// assert_eq!("pattern too large for program capacity", err.reason());
+    ///
+    /// // find needs group 0 to report the match range
+    /// let err = Regex::<32, 0>::new("abc").unwrap_err();
+    /// assert_eq!((0, "no capture slot for the whole match"), (err.index(), err.reason()));
+    /// ```
+    ///
+    pub fn new(pattern: &str) -> Result<Self, err::InvalidFormat> {
+        let mut re = Self {
+            prog: [Inst::Match; INSTS],
+            len: 0,
+            ranges: [('\0', '\0'); INSTS],
+            nranges: 0,
+            groups: 1,
+        };
+        let mut c = Compiler { pattern, pos: 0, re: &mut re };
+        if CAPS == 0 { return Err(c.error("no capture slot for the whole match")); }
+        c.emit(Inst::Save(0))?;
+        c.alternation()?;
+        if c.peek().is_some() { return Err(c.error("unopened group")); }
+        c.emit(Inst::Save(1))?;
+        c.emit(Inst::Match)?;
+        Ok(re)
+    }
+
+    /// number of capture groups, group 0 included
+    pub const fn groups(&self) -> usize { self.groups }
+
+    /// number of instructions in the compiled program
+    pub const fn program_len(&self) -> usize { self.len }
+
+    fn class_match(&self, start: usize, len: usize, negated: bool, c: char) -> bool {
+        let found = self.ranges[start..start + len].iter().any(|(lo, hi)| (*lo..=*hi).contains(&c));
+        found != negated
+    }
+
+    /// follow the non consuming instructions from pc and add the threads reached
+    fn add(&self, list: &mut Threads<INSTS, CAPS>, pc: usize, mut caps: [[u32; 2]; CAPS],
+           text: &str, pos: usize) {
+        if list.contains(pc) { return; }
+        list.push(pc, caps);
+        match self.prog[pc] {
+            Inst::Jmp(t) => self.add(list, t, caps, text, pos),
+            Inst::Split(a, b) => {
+                self.add(list, a, caps, text, pos);
+                self.add(list, b, caps, text, pos);
+            },
+            Inst::Save(slot) => {
+                caps[slot / 2][slot % 2] = pos as u32;
+                self.add(list, pc + 1, caps, text, pos);
+            },
+            Inst::Bol if pos == 0 => self.add(list, pc + 1, caps, text, pos),
+            Inst::Eol if pos == text.len() => self.add(list, pc + 1, caps, text, pos),
+            Inst::WordBoundary(want) => {
+                let before = is_word(text[..pos].chars().next_back());
+                let after = is_word(text[pos..].chars().next());
+                if (before != after) == want { self.add(list, pc + 1, caps, text, pos) }
+            },
+            _ => {},
+        }
+    }
+
+    /// run the vm, return the captures of the leftmost first match
+    fn exec(&self, text: &str) -> Option<[[u32; 2]; CAPS]> {
+        if text.len() >= UNSET as usize { return None; }
+        let mut clist = Threads::<INSTS, CAPS>::new();
+        let mut nlist = Threads::<INSTS, CAPS>::new();
+        let mut matched = None;
+        let mut pos = 0;
+        loop {
+            // a new thread for a match starting here, with the lowest priority
+            if matched.is_none() {
+                self.add(&mut clist, 0, [[UNSET; 2]; CAPS], text, pos);
+            }
+            if clist.len == 0 { break; }
+            let c = text[pos..].chars().next();
+            let next = pos + c.map_or(0, char::len_utf8);
+            for i in 0..clist.len {
+                let pc = clist.pcs[i];
+                let step = match (self.prog[pc], c) {
+                    (Inst::Char(x), Some(c)) => x == c,
+                    (Inst::Any, Some(c)) => c != '\n',
+                    (Inst::Class { start, len, negated }, Some(c)) => self.class_match(start, len, negated, c),
+                    (Inst::Match, _) => {
+                        matched = Some(clist.caps[i]);
+                        // threads after this one have lower priority
+                        break;
+                    },
+                    _ => false,
+                };
+                if step { self.add(&mut nlist, pc + 1, clist.caps[i], text, next); }
+            }
+            if c.is_none() { break; }
+            pos = next;
+            std::mem::swap(&mut clist, &mut nlist);
+            nlist.len = 0;
+        }
+        matched
+    }
+
+    /// check if the regex match anywhere in text
+    pub fn is_match(&self, text: impl AsRef<str>) -> bool {
+        self.exec(text.as_ref()).is_some()
+    }
+
+    /// byte range of the leftmost first match
+    /// # example
+    /// ```
+    /// use cbfr::regex::Regex;
+    ///
+    /// let re: Regex = Regex::new(r"\bcat|dog").unwrap();
+    /// assert_eq!(Some(7..10), re.find("bobcat cat"));
+    /// assert_eq!(None, re.find("bobcats"));
+    /// ```
+    ///
+    pub fn find(&self, text: impl AsRef<str>) -> Option<Range<usize>> {
+        let caps = self.captures(text)?;
+        caps.get(0)
+    }
+
+    /// capture groups of the leftmost first match
+    /// # example
+    /// ```
+    /// use cbfr::regex::Regex;
+    ///
+    /// let kv: Regex<64, 3> = Regex::new(r"^(\w+)\s*=\s*(.*)$").unwrap();
+    /// let line = "timeout = 30s";
+    /// let caps = kv.captures(line).unwrap();
+    ///
+    /// assert_eq!("timeout", &line[caps.get(1).unwrap()]);
+    /// assert_eq!("30s", &line[caps.get(2).unwrap()]);
+    /// assert!(kv.captures("= 1").is_none());
+    /// ```
+    ///
+    pub fn captures(&self, text: impl AsRef<str>) -> Option<Captures<CAPS>> {
+        let slots = self.exec(text.as_ref())?;
+        Some(Captures { slots, groups: self.groups })
+    }
+}
@@ -0,0 +1,437 @@
+use std::io;
+
+use crate::cb::{DEFCAPACITY, NecResult};
+use crate::errors as err;
+
+/// What BFRRING should do when a value is pushed into a full buffer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RingMode {
+    /// refuse the value and return NotEnoughCapacity (default)
+    #[default]
+    Reject,
+    /// drop the value on the opposite end to make room
+    Overwrite,
+}
+
+/// BFRRING is a circular byte buffer that live on the stack.
+/// Value can be pushed and popped at both ends in O(1),
+/// the head wraps around the end of the internal array instead of
+/// shifting the data like BFRDYN::prepend or BFRDYN::take_head does.
+/// When the buffer is full it either reject the new value or
+/// overwrite the oldest one, depending on its [RingMode].
+/// # example
+/// ```
+/// use cbfr::ring::BFRRING;
+///
+/// let mut r: BFRRING<8> = BFRRING::new();
+/// r.push_str("world").unwrap();
+/// r.push_front(b' ').unwrap();
+/// r.push_front(b'!').unwrap();
+///
+/// assert_eq!(Some(b'!'), r.pop_front());
+/// assert_eq!(Some(b'd'), r.pop_back());
+/// assert_eq!(" worl", r.make_contiguous().unwrap());
+/// ```
+#[derive(Debug, Clone)]
+pub struct BFRRING<const CAPACITY: usize = DEFCAPACITY> {
+    arr: [u8; CAPACITY],
+    head: usize,
+    len: usize,
+    mode: RingMode,
+}
+
+/// Create ring buffer instance from &str
+/// # Example
+/// ```
+/// use cbfr::ring::BFRRING;
+///
+/// let mut r: BFRRING<16> = "some string".into();
+/// assert_eq!("some string", r.make_contiguous().unwrap());
+/// ```
+/// # Panic
+/// Panic if "some string" len > 16
+///
+impl<const CAPACITY: usize> From<&str> for BFRRING<CAPACITY> {
+    fn from(value: &str) -> Self {
+        let mut me = Self::new();
+        assert!(value.len() <= CAPACITY, "Not enough capacity!");
+        me.arr[..value.len()].copy_from_slice(value.as_bytes());
+        me.len = value.len();
+        me
+    }
+}
+
+impl<const CAPACITY: usize> Default for BFRRING<CAPACITY> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<const CAPACITY: usize> BFRRING<CAPACITY> {
+    /// create new ring buffer that reject value when full
+    /// # example
+    /// ```
+    /// use cbfr::ring::BFRRING;
+    /// let r = BFRRING::<64>::new();
+    /// assert_eq!(0, r.len());
+    /// assert_eq!(64, r.capacity());
+    /// ```
+    ///
+    pub const fn new() -> Self {
+        Self::with_mode(RingMode::Reject)
+    }
+
+    /// create new ring buffer with the given [RingMode]
+    /// # example
+    /// ```
+    /// use cbfr::ring::{BFRRING, RingMode};
+    /// let mut r = BFRRING::<4>::with_mode(RingMode::Overwrite);
+    /// r.push_str("abcdef").unwrap();
+    /// assert_eq!("cdef", r.make_contiguous().unwrap());
+    /// ```
+    ///
+    pub const fn with_mode(mode: RingMode) -> Self {
+        Self { arr: [0u8; CAPACITY], head: 0, len: 0, mode }
+    }
+
+    /// get current [RingMode]
+    pub const fn mode(&self) -> RingMode { self.mode }
+
+    /// change [RingMode], existing data is left untouched
+    pub fn set_mode(&mut self, mode: RingMode) { self.mode = mode }
+
+    /// get buffer capacity
+    pub const fn capacity(&self) -> usize { CAPACITY }
+
+    /// get buffer len
+    pub const fn len(&self) -> usize { self.len }
+
+    /// check if buffer is empty
+    pub const fn is_empty(&self) -> bool { self.len == 0 }
+
+    /// check if buffer is full
+    /// # example
+    /// ```
+    /// use cbfr::ring::BFRRING;
+    /// let mut r: BFRRING<2> = "ab".into();
+    /// assert!(r.is_full());
+    /// r.pop_front();
+    /// assert!(!r.is_full());
+    /// ```
+    ///
+    pub const fn is_full(&self) -> bool { self.len == CAPACITY }
+
+    /// translate logical position (0 is the front) into index of internal array
+    /// caller must make sure pos < CAPACITY
+    const fn idx(&self, pos: usize) -> usize {
+        let i = self.head + pos;
+        if i >= CAPACITY { i - CAPACITY } else { i }
+    }
+
+    /// get byte at logical position, 0 is the front
+    /// # example
+    /// ```
+    /// use cbfr::ring::BFRRING;
+    /// let mut r: BFRRING<4> = "bcd".into();
+    /// r.push_front(b'a').unwrap();
+    /// assert_eq!(Some(b'a'), r.get(0));
+    /// assert_eq!(Some(b'd'), r.get(3));
+    /// assert_eq!(None, r.get(4));
+    /// ```
+    ///
+    pub const fn get(&self, pos: usize) -> Option<u8> {
+        if pos < self.len { Some(self.arr[self.idx(pos)]) } else { None }
+    }
+
+    /// get the first byte, or None if buffer is empty
+    pub const fn front(&self) -> Option<u8> { self.get(0) }
+
+    /// get the last byte, or None if buffer is empty
+    pub const fn back(&self) -> Option<u8> {
+        if self.len > 0 { self.get(self.len - 1) } else { None }
+    }
+
+    /// push a byte to the back.
+    /// When the buffer is full, Reject mode return NotEnoughCapacity
+    /// and Overwrite mode drop the front byte (the oldest one)
+    /// # example
+    /// ```
+    /// use cbfr::ring::{BFRRING, RingMode};
+    ///
+    /// let mut a: BFRRING<2> = "ab".into();
+    /// assert!(a.push_back(b'c').is_err());
+    ///
+    /// let mut b = BFRRING::<2>::with_mode(RingMode::Overwrite);
+    /// b.push_str("ab").unwrap();
+    /// b.push_back(b'c').unwrap();
+    /// assert_eq!("bc", b.make_contiguous().unwrap());
+    /// ```
+    ///
+    pub fn push_back(&mut self, value: u8) -> NecResult {
+        if self.is_full() {
+            if self.mode == RingMode::Reject || CAPACITY == 0 {
+                return Err(err::NotEnoughCapacity::throw(CAPACITY, self.len + 1));
+            }
+            self.pop_front();
+        }
+        let i = self.idx(self.len);
+        self.arr[i] = value;
+        self.len += 1;
+        Ok(())
+    }
+
+    /// push a byte to the front.
+    /// When the buffer is full, Reject mode return NotEnoughCapacity
+    /// and Overwrite mode drop the back byte
+    /// # example
+    /// ```
+    /// use cbfr::ring::{BFRRING, RingMode};
+    ///
+    /// let mut r = BFRRING::<3>::with_mode(RingMode::Overwrite);
+    /// r.push_str("bcd").unwrap();
+    /// r.push_front(b'a').unwrap();
+    /// assert_eq!("abc", r.make_contiguous().unwrap());
+    /// ```
+    ///
+    pub fn push_front(&mut self, value: u8) -> NecResult {
+        if self.is_full() {
+            if self.mode == RingMode::Reject || CAPACITY == 0 {
+                return Err(err::NotEnoughCapacity::throw(CAPACITY, self.len + 1));
+            }
+            self.pop_back();
+        }
+        self.head = if self.head == 0 { CAPACITY - 1 } else { self.head - 1 };
+        self.arr[self.head] = value;
+        self.len += 1;
+        Ok(())
+    }
+
+    /// pop a byte from the back
+    /// # example
+    /// ```
+    /// use cbfr::ring::BFRRING;
+    /// let mut r: BFRRING<8> = "ab".into();
+    /// assert_eq!(Some(b'b'), r.pop_back());
+    /// assert_eq!(Some(b'a'), r.pop_back());
+    /// assert_eq!(None, r.pop_back());
+    /// ```
+    ///
+    pub fn pop_back(&mut self) -> Option<u8> {
+        if self.len == 0 { return None; }
+        self.len -= 1;
+        let i = self.idx(self.len);
+        let value = self.arr[i];
+        self.arr[i] = 0u8;
+        Some(value)
+    }
+
+    /// pop a byte from the front
+    /// # example
+    /// ```
+    /// use cbfr::ring::BFRRING;
+    /// let mut r: BFRRING<8> = "ab".into();
+    /// assert_eq!(Some(b'a'), r.pop_front());
+    /// assert_eq!(Some(b'b'), r.pop_front());
+    /// assert_eq!(None, r.pop_front());
+    /// ```
+    ///
+    pub fn pop_front(&mut self) -> Option<u8> {
+        if self.len == 0 { return None; }
+        let value = self.arr[self.head];
+        self.arr[self.head] = 0u8;
+        self.head = self.idx(1);
+        self.len -= 1;
+        if self.len == 0 { self.head = 0; }
+        Some(value)
+    }
+
+    /// push &str to the back.
+    /// Reject mode only accept the text if the whole text fit,
+    /// Overwrite mode drop whole chars from the front to make room
+    /// (only the tail of the text is kept if it is larger than the capacity)
+    /// # example
+    /// ```
+    /// use cbfr::ring::{BFRRING, RingMode};
+    ///
+    /// let mut a: BFRRING<4> = "ab".into();
+    /// assert!(a.push_str("cde").is_err());
+    /// assert_eq!("ab", a.make_contiguous().unwrap());
+    ///
+    /// let mut b = BFRRING::<4>::with_mode(RingMode::Overwrite);
+    /// b.push_str("né").unwrap();
+    /// b.push_str("xyz").unwrap();
+    /// assert_eq!("xyz", b.make_contiguous().unwrap());
+    /// ```
+    ///
+    pub fn push_str(&mut self, text: &str) -> NecResult {
+        let total_len = self.len + text.len();
+        if total_len > CAPACITY {
+            if self.mode == RingMode::Reject {
+                return Err(err::NotEnoughCapacity::throw(CAPACITY, total_len));
+            }
+            let mut text = text;
+            while text.len() > CAPACITY {
+                let mut cut = text.len() - CAPACITY;
+                while !text.is_char_boundary(cut) { cut += 1; }
+                text = &text[cut..];
+            }
+            while self.len + text.len() > CAPACITY {
+                self.pop_front();
+                self.skip_continuation();
+            }
+            return self.push_bytes(text.as_bytes());
+        }
+        self.push_bytes(text.as_bytes())
+    }
+
+    /// drop leading utf-8 continuation bytes so the front lies on a char boundary
+    fn skip_continuation(&mut self) {
+        while let Some(b) = self.front() {
+            if b & 0b1100_0000 != 0b1000_0000 { break; }
+            self.pop_front();
+        }
+    }
+
+    /// copy bytes to the back, caller must make sure they fit
+    fn push_bytes(&mut self, bytes: &[u8]) -> NecResult {
+        let start = self.idx(self.len);
+        let first = bytes.len().min(CAPACITY - start);
+        self.arr[start..start + first].copy_from_slice(&bytes[..first]);
+        self.arr[..bytes.len() - first].copy_from_slice(&bytes[first..]);
+        self.len += bytes.len();
+        Ok(())
+    }
+
+    /// return the content as two slices, the first slice is the front.
+    /// The second slice is empty unless the data wraps around the end
+    /// of the internal array.
+    /// # example
+    /// ```
+    /// use cbfr::ring::BFRRING;
+    ///
+    /// let mut r: BFRRING<4> = "abcd".into();
+    /// r.pop_front();
+    /// r.pop_front();
+    /// r.push_str("ef").unwrap();
+    ///
+    /// let (a, b) = r.as_slices();
+    /// assert_eq!(b"cd", a);
+    /// assert_eq!(b"ef", b);
+    /// ```
+    ///
+    pub fn as_slices(&self) -> (&[u8], &[u8]) {
+        let end = self.head + self.len;
+        if end <= CAPACITY {
+            (&self.arr[self.head..end], &[])
+        } else {
+            (&self.arr[self.head..], &self.arr[..end - CAPACITY])
+        }
+    }
+
+    /// rotate the internal array so the content start at index 0,
+    /// then return the content as &str.
+    /// Return Utf8Error if the content is not valid utf-8 (raw bytes pushed with push_back,
+    /// a lead byte dropped in Overwrite mode, binary data written through io::Write),
+    /// the bytes stay available with [BFRRING::as_slices].
+    /// # example
+    /// ```
+    /// use cbfr::ring::{BFRRING, RingMode};
+    ///
+    /// let mut r: BFRRING<6> = "abcdef".into();
+    /// r.pop_front();
+    /// r.push_back(b'g').unwrap();
+    ///
+    /// assert_eq!("bcdefg", r.make_contiguous().unwrap());
+    /// assert_eq!(b"bcdefg", r.as_slices().0);
+    ///
+    /// let mut o = BFRRING::<4>::with_mode(RingMode::Overwrite);
+    /// o.push_str("aé").unwrap();
+    /// for b in *b"bcd" {
+    ///     o.push_back(b).unwrap();   // drop 'a' then the first byte of 'é'
+    /// }
+    /// assert!(o.make_contiguous().is_err());
+    /// assert_eq!(4, o.len());
+    /// assert_eq!(&[0xa9, b'b', b'c', b'd'], o.as_slices().0);
+    /// ```
+    ///
+    pub fn make_contiguous(&mut self) -> Result<&str, std::str::Utf8Error> {
+        self.arr.rotate_left(self.head);
+        self.head = 0;
+        std::str::from_utf8(&self.arr[..self.len])
+    }
+
+    /// remove all data inside buffer
+    /// # example
+    /// ```
+    /// use cbfr::ring::BFRRING;
+    /// let mut r: BFRRING<8> = "abc".into();
+    /// r.clear();
+    /// assert!(r.is_empty());
+    /// ```
+    ///
+    pub fn clear(&mut self) {
+        self.arr = [0u8; CAPACITY];
+        self.head = 0;
+        self.len = 0;
+    }
+}
+
+/// read pop bytes from the front, an empty buffer read as end of file
+/// # example
+/// ```
+/// use std::io::Read;
+/// use cbfr::ring::BFRRING;
+///
+/// let mut r: BFRRING<16> = "hello world".into();
+/// let mut out = [0u8; 5];
+/// r.read_exact(&mut out).unwrap();
+///
+/// assert_eq!(b"hello", &out);
+/// assert_eq!(" world", r.make_contiguous().unwrap());
+/// ```
+///
+impl<const CAPACITY: usize> io::Read for BFRRING<CAPACITY> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = buf.len().min(self.len);
+        let (a, b) = self.as_slices();
+        let first = n.min(a.len());
+        buf[..first].copy_from_slice(&a[..first]);
+        buf[first..n].copy_from_slice(&b[..n - first]);
+        for _ in 0..n { self.pop_front(); }
+        Ok(n)
+    }
+}
+
+/// write push bytes to the back.
+/// Reject mode write only what fit (write_all fail with WriteZero when full),
+/// Overwrite mode always accept every bytes and drop the oldest ones
+/// # example
+/// ```
+/// use std::io::Write;
+/// use cbfr::ring::{BFRRING, RingMode};
+///
+/// let mut a: BFRRING<4> = BFRRING::new();
+/// assert_eq!(4, a.write(b"abcdef").unwrap());
+/// assert!(a.write_all(b"g").is_err());
+///
+/// let mut b = BFRRING::<4>::with_mode(RingMode::Overwrite);
+/// b.write_all(b"abcdef").unwrap();
+/// assert_eq!("cdef", b.make_contiguous().unwrap());
+/// ```
+///
+impl<const CAPACITY: usize> io::Write for BFRRING<CAPACITY> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self.mode {
+            RingMode::Reject => {
+                let n = buf.len().min(CAPACITY - self.len);
+                self.push_bytes(&buf[..n]).ok();
+                Ok(n)
+            },
+            RingMode::Overwrite => {
+                let keep = &buf[buf.len().saturating_sub(CAPACITY)..];
+                while self.len + keep.len() > CAPACITY { self.pop_front(); }
+                self.push_bytes(keep).ok();
+                Ok(buf.len())
+            }
+        }
+    }
+    fn flush(&mut self) -> io::Result<()> { Ok(()) }
+}
@@ -0,0 +1,301 @@
+//! Byte scanning routines (memchr like).
+//! On x86_64 they use SSE2, or AVX2 when the cpu support it (detected once at runtime),
+//! other targets and short inputs use the portable [scalar] implementation.
+//! # example
+//! ```
+//! use cbfr::scan;
+//!
+//! let csv = b"id,name;age\n";
+//! assert_eq!(Some(2), scan::find_byte(csv, b','));
+//! assert_eq!(Some(7), scan::find_any_of_2(csv, b';', b'\n'));
+//! assert_eq!(1, scan::count_byte(csv, b'\n'));
+//! ```
+
+/// pick the widest implementation available for the input
+macro_rules! dispatch {
+    ($func:ident($haystack:ident $(, $arg:ident)*)) => {{
+        #[cfg(target_arch = "x86_64")]
+        {
+            if $haystack.len() >= avx2::WIDTH && has_avx2() {
+                // SAFETY: avx2 support was checked at runtime
+                return unsafe { avx2::$func($haystack $(, $arg)*) };
+            }
+            if $haystack.len() >= sse2::WIDTH {
+                // SAFETY: sse2 is part of the x86_64 baseline
+                return unsafe { sse2::$func($haystack $(, $arg)*) };
+            }
+        }
+        scalar::$func($haystack $(, $arg)*)
+    }};
+}
+
+/// first position of a byte
+pub fn find_byte(haystack: &[u8], n1: u8) -> Option<usize> {
+    dispatch!(find_byte(haystack, n1))
+}
+
+/// first position of any of 2 bytes
+pub fn find_any_of_2(haystack: &[u8], n1: u8, n2: u8) -> Option<usize> {
+    dispatch!(find_any_of_2(haystack, n1, n2))
+}
+
+/// first position of any of 3 bytes
+pub fn find_any_of_3(haystack: &[u8], n1: u8, n2: u8, n3: u8) -> Option<usize> {
+    dispatch!(find_any_of_3(haystack, n1, n2, n3))
+}
+
+/// last position of a byte
+pub fn rfind_byte(haystack: &[u8], n1: u8) -> Option<usize> {
+    dispatch!(rfind_byte(haystack, n1))
+}
+
+/// first position of a byte different from `n1`
+/// # example
+/// ```
+/// use cbfr::scan;
+///
+/// assert_eq!(Some(3), scan::find_not_byte(b"   x  ", b' '));
+/// assert_eq!(Some(3), scan::rfind_not_byte(b"   x  ", b' '));
+/// assert_eq!(None, scan::find_not_byte(b"    ", b' '));
+/// ```
+///
+pub fn find_not_byte(haystack: &[u8], n1: u8) -> Option<usize> {
+    dispatch!(find_not_byte(haystack, n1))
+}
+
+/// last position of a byte different from `n1`
+pub fn rfind_not_byte(haystack: &[u8], n1: u8) -> Option<usize> {
+    dispatch!(rfind_not_byte(haystack, n1))
+}
+
+/// number of occurrences of a byte
+pub fn count_byte(haystack: &[u8], n1: u8) -> usize {
+    dispatch!(count_byte(haystack, n1))
+}
+
+/// check (once) if the cpu support AVX2
+#[cfg(target_arch = "x86_64")]
+fn has_avx2() -> bool {
+    use std::sync::atomic::{AtomicU8, Ordering};
+    // 0 = not checked yet, 1 = no, 2 = yes
+    static AVX2: AtomicU8 = AtomicU8::new(0);
+    match AVX2.load(Ordering::Relaxed) {
+        0 => {
+            let yes = std::is_x86_feature_detected!("avx2");
+            AVX2.store(if yes { 2 } else { 1 }, Ordering::Relaxed);
+            yes
+        },
+        state => state == 2,
+    }
+}
+
+/// Portable byte at a time implementation, also used for the tail of the vector paths
+pub mod scalar {
+    /// first position of a byte equal to any needle (or equal to none of them if `invert`)
+    #[inline]
+    pub(crate) fn find<const K: usize>(haystack: &[u8], needles: [u8; K], invert: bool) -> Option<usize> {
+        haystack.iter().position(|b| needles.contains(b) != invert)
+    }
+
+    /// last position of a byte equal to any needle (or equal to none of them if `invert`)
+    #[inline]
+    pub(crate) fn rfind<const K: usize>(haystack: &[u8], needles: [u8; K], invert: bool) -> Option<usize> {
+        haystack.iter().rposition(|b| needles.contains(b) != invert)
+    }
+
+    /// first position of a byte
+    pub fn find_byte(haystack: &[u8], n1: u8) -> Option<usize> { find(haystack, [n1], false) }
+
+    /// first position of any of 2 bytes
+    pub fn find_any_of_2(haystack: &[u8], n1: u8, n2: u8) -> Option<usize> { find(haystack, [n1, n2], false) }
+
+    /// first position of any of 3 bytes
+    pub fn find_any_of_3(haystack: &[u8], n1: u8, n2: u8, n3: u8) -> Option<usize> {
+        find(haystack, [n1, n2, n3], false)
+    }
+
+    /// last position of a byte
+    pub fn rfind_byte(haystack: &[u8], n1: u8) -> Option<usize> { rfind(haystack, [n1], false) }
+
+    /// first position of a byte different from `n1`
+    pub fn find_not_byte(haystack: &[u8], n1: u8) -> Option<usize> { find(haystack, [n1], true) }
+
+    /// last position of a byte different from `n1`
+    pub fn rfind_not_byte(haystack: &[u8], n1: u8) -> Option<usize> { rfind(haystack, [n1], true) }
+
+    /// number of occurrences of a byte
+    pub fn count_byte(haystack: &[u8], n1: u8) -> usize {
+        haystack.iter().filter(|b| **b == n1).count()
+    }
+}
+
+/// generate a vector implementation from its intrinsics.
+/// Every function is unsafe, only call them (from [dispatch]) when the cpu support the feature
+#[cfg(target_arch = "x86_64")]
+macro_rules! vector_impl {
+    ($feature:literal, $width:literal, $vec:ty,
+     $load:ident, $set1:ident, $cmpeq:ident, $or:ident, $movemask:ident) => {
+        use std::arch::x86_64::*;
+        use super::scalar;
+
+        /// bytes compared per step
+        pub const WIDTH: usize = $width;
+
+        /// every bit of a chunk mask set
+        const FULL: u32 = u32::MAX >> (32 - $width);
+
+        /// bit i is set if byte i of the chunk at `pos` match any needle
+        #[target_feature(enable = $feature)]
+        unsafe fn mask<const K: usize>(haystack: &[u8], pos: usize, splat: &[$vec; K], invert: bool) -> u32 {
+            debug_assert!(pos + $width <= haystack.len());
+            // caller keep pos + WIDTH within haystack, load is unaligned
+            let chunk = $load(haystack.as_ptr().add(pos) as *const $vec);
+            let mut eq = $cmpeq(chunk, splat[0]);
+            let mut k = 1;
+            while k < K {
+                eq = $or(eq, $cmpeq(chunk, splat[k]));
+                k += 1;
+            }
+            let m = $movemask(eq) as u32;
+            if invert { !m & FULL } else { m }
+        }
+
+        #[target_feature(enable = $feature)]
+        unsafe fn find<const K: usize>(haystack: &[u8], needles: [u8; K], invert: bool) -> Option<usize> {
+            let splat = needles.map(|n| $set1(n as i8));
+            let mut pos = 0;
+            while pos + $width <= haystack.len() {
+                let m = mask(haystack, pos, &splat, invert);
+                if m != 0 { return Some(pos + m.trailing_zeros() as usize); }
+                pos += $width;
+            }
+            scalar::find(&haystack[pos..], needles, invert).map(|i| pos + i)
+        }
+
+        #[target_feature(enable = $feature)]
+        unsafe fn rfind<const K: usize>(haystack: &[u8], needles: [u8; K], invert: bool) -> Option<usize> {
+            let splat = needles.map(|n| $set1(n as i8));
+            let mut end = haystack.len();
+            while end >= $width {
+                let m = mask(haystack, end - $width, &splat, invert);
+                if m != 0 { return Some(end - $width + 31 - m.leading_zeros() as usize); }
+                end -= $width;
+            }
+            scalar::rfind(&haystack[..end], needles, invert)
+        }
+
+        /// first position of a byte
+        #[target_feature(enable = $feature)]
+        pub unsafe fn find_byte(haystack: &[u8], n1: u8) -> Option<usize> { find(haystack, [n1], false) }
+
+        /// first position of any of 2 bytes
+        #[target_feature(enable = $feature)]
+        pub unsafe fn find_any_of_2(haystack: &[u8], n1: u8, n2: u8) -> Option<usize> {
+            find(haystack, [n1, n2], false)
+        }
+
+        /// first position of any of 3 bytes
+        #[target_feature(enable = $feature)]
+        pub unsafe fn find_any_of_3(haystack: &[u8], n1: u8, n2: u8, n3: u8) -> Option<usize> {
+            find(haystack, [n1, n2, n3], false)
+        }
+
+        /// last position of a byte
+        #[target_feature(enable = $feature)]
+        pub unsafe fn rfind_byte(haystack: &[u8], n1: u8) -> Option<usize> { rfind(haystack, [n1], false) }
+
+        /// first position of a byte different from `n1`
+        #[target_feature(enable = $feature)]
+        pub unsafe fn find_not_byte(haystack: &[u8], n1: u8) -> Option<usize> { find(haystack, [n1], true) }
+
+        /// last position of a byte different from `n1`
+        #[target_feature(enable = $feature)]
+        pub unsafe fn rfind_not_byte(haystack: &[u8], n1: u8) -> Option<usize> { rfind(haystack, [n1], true) }
+
+        /// number of occurrences of a byte
+        #[target_feature(enable = $feature)]
+        pub unsafe fn count_byte(haystack: &[u8], n1: u8) -> usize {
+            let splat = [$set1(n1 as i8)];
+            let mut count = 0;
+            let mut pos = 0;
+            while pos + $width <= haystack.len() {
+                count += mask(haystack, pos, &splat, false).count_ones() as usize;
+                pos += $width;
+            }
+            count + scalar::count_byte(&haystack[pos..], n1)
+        }
+    };
+}
+
+/// 16 bytes per step, always available on x86_64
+#[cfg(target_arch = "x86_64")]
+mod sse2 {
+    vector_impl!("sse2", 16, __m128i,
+        _mm_loadu_si128, _mm_set1_epi8, _mm_cmpeq_epi8, _mm_or_si128, _mm_movemask_epi8);
+}
+
+/// 32 bytes per step, only call after checking [has_avx2]
+#[cfg(target_arch = "x86_64")]
+mod avx2 {
+    vector_impl!("avx2", 32, __m256i,
+        _mm256_loadu_si256, _mm256_set1_epi8, _mm256_cmpeq_epi8, _mm256_or_si256, _mm256_movemask_epi8);
+}
+
+/// wrap every kernel function into a safe one that check the cpu feature first
+#[cfg(target_arch = "x86_64")]
+macro_rules! checked_impl {
+    ($kernel:ident, $feature:tt) => {
+        pub mod $kernel {
+            use crate::scan::$kernel as kernel;
+
+            fn check() {
+                assert!(std::is_x86_feature_detected!($feature), concat!($feature, " not supported by this cpu"));
+            }
+
+            // SAFETY (every function below): check() panic unless the cpu support the feature
+            pub fn find_byte(haystack: &[u8], n1: u8) -> Option<usize> {
+                check();
+                unsafe { kernel::find_byte(haystack, n1) }
+            }
+
+            pub fn find_any_of_2(haystack: &[u8], n1: u8, n2: u8) -> Option<usize> {
+                check();
+                unsafe { kernel::find_any_of_2(haystack, n1, n2) }
+            }
+
+            pub fn find_any_of_3(haystack: &[u8], n1: u8, n2: u8, n3: u8) -> Option<usize> {
+                check();
+                unsafe { kernel::find_any_of_3(haystack, n1, n2, n3) }
+            }
+
+            pub fn rfind_byte(haystack: &[u8], n1: u8) -> Option<usize> {
+                check();
+                unsafe { kernel::rfind_byte(haystack, n1) }
+            }
+
+            pub fn find_not_byte(haystack: &[u8], n1: u8) -> Option<usize> {
+                check();
+                unsafe { kernel::find_not_byte(haystack, n1) }
+            }
+
+            pub fn rfind_not_byte(haystack: &[u8], n1: u8) -> Option<usize> {
+                check();
+                unsafe { kernel::rfind_not_byte(haystack, n1) }
+            }
+
+            pub fn count_byte(haystack: &[u8], n1: u8) -> usize {
+                check();
+                unsafe { kernel::count_byte(haystack, n1) }
+            }
+        }
+    };
+}
+
+/// Test only entry points that force one vector kernel whatever the input length,
+/// each call panic if the cpu does not support the feature
+#[cfg(target_arch = "x86_64")]
+#[doc(hidden)]
+pub mod forced {
+    checked_impl!(sse2, "sse2");
+    checked_impl!(avx2, "avx2");
+}
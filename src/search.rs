@@ -0,0 +1,157 @@
+/// Finder is a precompiled substring searcher using the two-way algorithm
+/// (Crochemore-Perrin). Searching take O(n + m) time and O(1) space,
+/// there is no table to allocate so a Finder can be built in const context.
+/// # example
+/// ```
+/// use cbfr::search::Finder;
+///
+/// const SEP: Finder = Finder::new("\r\n");
+///
+/// assert_eq!(Some(5), SEP.find(b"Host:\r\nAccept:\r\n"));
+/// let lines: Vec<usize> = SEP.find_iter(b"a\r\nb\r\nc").collect();
+/// assert_eq!(vec![1, 4], lines);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Finder<'n> {
+    needle: &'n [u8],
+    crit: usize,
+    period: usize,
+    /// needle is not periodic, matched prefix of a shift can't be remembered
+    long_period: bool,
+}
+
+/// maximal suffix of `arr` for the byte order (or the reversed order),
+/// return (start of the suffix, its period)
+const fn maximal_suffix(arr: &[u8], reversed: bool) -> (usize, usize) {
+    let mut left = 0;
+    let mut right = 1;
+    let mut offset = 0;
+    let mut period = 1;
+    while right + offset < arr.len() {
+        let a = arr[right + offset];
+        let b = arr[left + offset];
+        if (!reversed && a < b) || (reversed && a > b) {
+            right += offset + 1;
+            offset = 0;
+            period = right - left;
+        } else if a == b {
+            if offset + 1 == period {
+                right += offset + 1;
+                offset = 0;
+            } else {
+                offset += 1;
+            }
+        } else {
+            left = right;
+            right += 1;
+            offset = 0;
+            period = 1;
+        }
+    }
+    (left, period)
+}
+
+const fn max(a: usize, b: usize) -> usize { if a > b { a } else { b } }
+
+impl<'n> Finder<'n> {
+    /// precompile a needle
+    pub const fn new(needle: &'n str) -> Self { Self::from_bytes(needle.as_bytes()) }
+
+    /// precompile a needle made of arbitrary bytes
+    pub const fn from_bytes(needle: &'n [u8]) -> Self {
+        let (l1, p1) = maximal_suffix(needle, false);
+        let (l2, p2) = maximal_suffix(needle, true);
+        let (crit, period) = if l1 > l2 { (l1, p1) } else { (l2, p2) };
+        // periodic needle: needle[..crit] is a suffix of needle[..crit + period]
+        let mut periodic = crit + period <= needle.len();
+        let mut i = 0;
+        while periodic && i < crit {
+            if needle[i] != needle[i + period] { periodic = false; }
+            i += 1;
+        }
+        if periodic {
+            Self { needle, crit, period, long_period: false }
+        } else {
+            let period = max(crit, needle.len() - crit) + 1;
+            Self { needle, crit, period, long_period: true }
+        }
+    }
+
+    /// get the needle
+    pub const fn needle(&self) -> &'n [u8] { self.needle }
+
+    /// get the needle length in bytes
+    pub const fn len(&self) -> usize { self.needle.len() }
+
+    /// check if needle is empty, an empty needle match at every position
+    pub const fn is_empty(&self) -> bool { self.needle.is_empty() }
+
+    /// find the first match in haystack, return its byte position
+    pub const fn find(&self, haystack: &[u8]) -> Option<usize> {
+        self.find_at(haystack, 0)
+    }
+
+    /// find the first match in haystack starting at or after `start`
+    /// # example
+    /// ```
+    /// use cbfr::search::Finder;
+    ///
+    /// let f = Finder::new("ab");
+    /// assert_eq!(Some(0), f.find_at(b"abcab", 0));
+    /// assert_eq!(Some(3), f.find_at(b"abcab", 1));
+    /// assert_eq!(None, f.find_at(b"abcab", 4));
+    /// ```
+    ///
+    pub const fn find_at(&self, haystack: &[u8], start: usize) -> Option<usize> {
+        let needle = self.needle;
+        let n = needle.len();
+        let mut pos = start;
+        let mut memory = 0;
+        while pos + n <= haystack.len() {
+            // right half, from the critical position forward
+            let mut i = if self.long_period { self.crit } else { max(self.crit, memory) };
+            while i < n && needle[i] == haystack[pos + i] { i += 1; }
+            if i < n {
+                pos += i - self.crit + 1;
+                memory = 0;
+                continue;
+            }
+            // left half, from the critical position backward
+            let lower = if self.long_period { 0 } else { memory };
+            let mut j = self.crit;
+            while j > lower && needle[j - 1] == haystack[pos + j - 1] { j -= 1; }
+            if j <= lower { return Some(pos); }
+            pos += self.period;
+            if !self.long_period { memory = n - self.period; }
+        }
+        None
+    }
+
+    /// check if haystack contain the needle
+    pub const fn is_in(&self, haystack: &[u8]) -> bool { self.find(haystack).is_some() }
+
+    /// iterate over the positions of non overlapping matches, left to right
+    pub fn find_iter<'h>(&self, haystack: &'h [u8]) -> FindIter<'n, 'h> {
+        FindIter { finder: *self, haystack, pos: 0 }
+    }
+}
+
+/// Iterator returned by [Finder::find_iter]
+#[derive(Debug, Clone)]
+pub struct FindIter<'n, 'h> {
+    finder: Finder<'n>,
+    haystack: &'h [u8],
+    pos: usize,
+}
+
+impl Iterator for FindIter<'_, '_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.pos > self.haystack.len() { return None; }
+        let found = self.finder.find_at(self.haystack, self.pos)?;
+        // an empty needle still has to move forward
+        self.pos = found + self.finder.len().max(1);
+        Some(found)
+    }
+}
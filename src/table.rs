@@ -0,0 +1,410 @@
+use crate::cb::DEFCAPACITY;
+use crate::errors as err;
+
+/// flag set on the len of a removed entry in the serialized blob
+const REMOVED: u32 = 1 << 31;
+
+/// size of blob header, entry count + arena len
+const HEADER: usize = 8;
+
+/// size of one serialized entry, start + len
+const ENTRY: usize = 8;
+
+/// Id of a string stored inside BFRTABLE
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Id(pub(crate) usize);
+
+impl Id {
+    /// position of the entry inside the table index
+    pub const fn index(self) -> usize { self.0 }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Entry {
+    start: usize,
+    len: usize,
+    removed: bool,
+}
+
+/// BFRTABLE packs many strings into one fixed byte arena with an offset index.
+/// - BYTES is the arena capacity, shared by every strings
+/// - ENTRIES is the maximum number of strings
+///
+/// Storing a lot of short strings this way wastes no space per string
+/// (unlike `[BFRDYN<32>; N]`) and does not allocate (unlike `Vec<String>`).
+/// Removed strings leave a hole until [BFRTABLE::compact] is called.
+/// # example
+/// ```
+/// use cbfr::table::BFRTABLE;
+///
+/// let mut t: BFRTABLE<64, 8> = BFRTABLE::new();
+/// let apple = t.push("apple").unwrap();
+/// let kiwi = t.push("kiwi").unwrap();
+///
+/// assert_eq!(Some("apple"), t.get(apple));
+/// assert_eq!(Some("kiwi"), t.get(kiwi));
+/// assert_eq!(9, t.bytes_used());
+/// ```
+#[derive(Debug, Clone)]
+pub struct BFRTABLE<const BYTES: usize = DEFCAPACITY, const ENTRIES: usize = 32> {
+    arena: [u8; BYTES],
+    used: usize,
+    entries: [Entry; ENTRIES],
+    count: usize,
+    removed: usize,
+    sorted: bool,
+}
+
+impl<const BYTES: usize, const ENTRIES: usize> Default for BFRTABLE<BYTES, ENTRIES> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<const BYTES: usize, const ENTRIES: usize> BFRTABLE<BYTES, ENTRIES> {
+    /// create new empty table
+    /// # example
+    /// ```
+    /// use cbfr::table::BFRTABLE;
+    /// let t = BFRTABLE::<1024, 64>::new();
+    /// assert_eq!(0, t.len());
+    /// assert_eq!(64, t.capacity());
+    /// ```
+    ///
+    pub const fn new() -> Self {
+        Self {
+            arena: [0u8; BYTES],
+            used: 0,
+            entries: [Entry { start: 0, len: 0, removed: false }; ENTRIES],
+            count: 0,
+            removed: 0,
+            sorted: true,
+        }
+    }
+
+    /// get the maximum number of strings
+    pub const fn capacity(&self) -> usize { ENTRIES }
+
+    /// get the number of strings, removed strings are not counted
+    pub const fn len(&self) -> usize { self.count - self.removed }
+
+    /// check if table has no string
+    pub const fn is_empty(&self) -> bool { self.len() == 0 }
+
+    /// get the number of arena bytes in use (including holes left by removed strings)
+    pub const fn bytes_used(&self) -> usize { self.used }
+
+    /// check if strings are sorted by their bytes, removed strings are included
+    pub const fn is_sorted(&self) -> bool { self.sorted }
+
+    fn text(&self, e: &Entry) -> &str {
+        // safety: only &str are copied into the arena
+        // and from_blob validates every entry
+        unsafe { std::str::from_utf8_unchecked(&self.arena[e.start..e.start + e.len]) }
+    }
+
+    /// append a string, return its Id
+    /// # example
+    /// ```
+    /// use cbfr::table::BFRTABLE;
+    ///
+    /// let mut t: BFRTABLE<8, 2> = BFRTABLE::new();
+    /// assert!(t.push("abcd").is_ok());
+    /// assert!(t.push("too long").is_err());    // arena is full
+    /// assert!(t.push("ef").is_ok());
+    /// assert!(t.push("g").is_err());           // index is full
+    /// ```
+    ///
+    pub fn push(&mut self, text: &str) -> Result<Id, err::NotEnoughCapacity> {
+        if self.count == ENTRIES {
+            return Err(err::NotEnoughCapacity::throw(ENTRIES, self.count + 1));
+        }
+        let total_len = self.used + text.len();
+        if total_len > BYTES {
+            return Err(err::NotEnoughCapacity::throw(BYTES, total_len));
+        }
+        if self.count > 0 {
+            let last = self.entries[self.count - 1];
+            self.sorted = self.sorted && self.text(&last) <= text;
+        }
+        self.arena[self.used..total_len].copy_from_slice(text.as_bytes());
+        self.entries[self.count] = Entry { start: self.used, len: text.len(), removed: false };
+        self.used = total_len;
+        self.count += 1;
+        Ok(Id(self.count - 1))
+    }
+
+    /// get a string by Id, return None if it was removed
+    pub fn get(&self, id: Id) -> Option<&str> {
+        let e = self.entries[..self.count].get(id.0)?;
+        if e.removed { None } else { Some(self.text(e)) }
+    }
+
+    /// remove a string, return false if it was already removed.
+    /// The arena space is given back by [BFRTABLE::compact]
+    /// # example
+    /// ```
+    /// use cbfr::table::BFRTABLE;
+    ///
+    /// let mut t: BFRTABLE<64, 8> = BFRTABLE::new();
+    /// let id = t.push("gone").unwrap();
+    /// assert!(t.remove(id));
+    /// assert!(!t.remove(id));
+    /// assert_eq!(None, t.get(id));
+    /// ```
+    ///
+    pub fn remove(&mut self, id: Id) -> bool {
+        match self.entries[..self.count].get_mut(id.0) {
+            Some(e) if !e.removed => {
+                e.removed = true;
+                self.removed += 1;
+                true
+            },
+            _ => false,
+        }
+    }
+
+    /// remove every string
+    pub fn clear(&mut self) {
+        self.used = 0;
+        self.count = 0;
+        self.removed = 0;
+        self.sorted = true;
+    }
+
+    /// iterate over (Id, &str) of every string that is not removed
+    /// # example
+    /// ```
+    /// use cbfr::table::BFRTABLE;
+    ///
+    /// let mut t: BFRTABLE<64, 8> = BFRTABLE::new();
+    /// t.push("a").unwrap();
+    /// let b = t.push("b").unwrap();
+    /// t.push("c").unwrap();
+    /// t.remove(b);
+    ///
+    /// let all: Vec<&str> = t.iter().map(|(_, s)| s).collect();
+    /// assert_eq!(vec!["a", "c"], all);
+    /// ```
+    ///
+    pub fn iter(&self) -> impl Iterator<Item = (Id, &str)> + '_ {
+        self.entries[..self.count].iter().enumerate()
+            .filter(|(_, e)| !e.removed)
+            .map(|(i, e)| (Id(i), self.text(e)))
+    }
+
+    /// sort the index by string bytes, the arena is left untouched.
+    /// Ids given before sort are no longer valid
+    /// # example
+    /// ```
+    /// use cbfr::table::BFRTABLE;
+    ///
+    /// let mut t: BFRTABLE<64, 8> = BFRTABLE::new();
+    /// for s in ["pear", "apple", "fig"] { t.push(s).unwrap(); }
+    /// assert!(!t.is_sorted());
+    ///
+    /// t.sort();
+    /// let all: Vec<&str> = t.iter().map(|(_, s)| s).collect();
+    /// assert_eq!(vec!["apple", "fig", "pear"], all);
+    /// ```
+    ///
+    pub fn sort(&mut self) {
+        let arena = &self.arena;
+        self.entries[..self.count]
+            .sort_unstable_by(|a, b| arena[a.start..a.start + a.len].cmp(&arena[b.start..b.start + b.len]));
+        self.sorted = true;
+    }
+
+    /// find a string, return its Id.
+    /// Use binary search when the table is sorted, otherwise scan every entry
+    /// # example
+    /// ```
+    /// use cbfr::table::BFRTABLE;
+    ///
+    /// let mut t: BFRTABLE<64, 8> = BFRTABLE::new();
+    /// for s in ["ant", "bee", "cat", "dog"] { t.push(s).unwrap(); }
+    ///
+    /// assert_eq!(Some(2), t.find("cat").map(|id| id.index()));
+    /// assert_eq!(None, t.find("cow"));
+    /// ```
+    ///
+    pub fn find(&self, text: &str) -> Option<Id> {
+        if self.sorted {
+            let pos = self.binary_search(text).ok()?;
+            // duplicates may be removed, look at the neighbours with the same text
+            let same = |i: &usize| self.text(&self.entries[*i]) == text;
+            let first = (0..pos).rev().take_while(same).last().unwrap_or(pos);
+            (first..self.count).take_while(same)
+                .find(|i| !self.entries[*i].removed)
+                .map(Id)
+        } else {
+            self.iter().find(|(_, s)| *s == text).map(|(id, _)| id)
+        }
+    }
+
+    /// binary search a sorted table, see slice::binary_search.
+    /// Removed strings take part in the search, and the result
+    /// is meaningless if the table is not sorted
+    pub fn binary_search(&self, text: &str) -> Result<usize, usize> {
+        self.entries[..self.count]
+            .binary_search_by(|e| self.text(e).as_bytes().cmp(text.as_bytes()))
+    }
+
+    /// move strings to fill the holes left by removed strings.
+    /// The order is kept but Ids given before compact are no longer valid
+    /// # example
+    /// ```
+    /// use cbfr::table::BFRTABLE;
+    ///
+    /// let mut t: BFRTABLE<64, 8> = BFRTABLE::new();
+    /// let a = t.push("aaaa").unwrap();
+    /// t.push("bb").unwrap();
+    /// t.remove(a);
+    /// assert_eq!(6, t.bytes_used());
+    ///
+    /// t.compact();
+    /// assert_eq!(2, t.bytes_used());
+    /// assert_eq!(Some("bb"), t.get(a));
+    /// ```
+    ///
+    pub fn compact(&mut self) {
+        let mut used = 0;
+        let mut count = 0;
+        // sort may have reordered the arena, so copy in arena order first
+        let mut order = [0usize; ENTRIES];
+        for (i, slot) in order[..self.count].iter_mut().enumerate() { *slot = i; }
+        let entries = &self.entries;
+        order[..self.count].sort_unstable_by_key(|i| entries[*i].start);
+        for &i in order[..self.count].iter() {
+            let e = &mut self.entries[i];
+            if e.removed { continue; }
+            self.arena.copy_within(e.start..e.start + e.len, used);
+            e.start = used;
+            used += e.len;
+        }
+        for i in 0..self.count {
+            if !self.entries[i].removed {
+                self.entries[count] = self.entries[i];
+                count += 1;
+            }
+        }
+        self.used = used;
+        self.count = count;
+        self.removed = 0;
+    }
+
+    /// number of bytes needed by [BFRTABLE::write_blob]
+    pub const fn blob_len(&self) -> usize { HEADER + self.count * ENTRY + self.used }
+
+    /// serialize index and arena into one blob and return the blob len.
+    /// Layout (every number is u32 little endian):
+    /// entry count, arena len, (start, len) of every entry, then the arena bytes.
+    /// A removed entry has the highest bit of its len set.
+    /// # example
+    /// ```
+    /// use cbfr::table::BFRTABLE;
+    ///
+    /// let mut t: BFRTABLE<64, 8> = BFRTABLE::new();
+    /// t.push("one").unwrap();
+    /// t.push("two").unwrap();
+    ///
+    /// let mut blob = [0u8; 64];
+    /// let n = t.write_blob(&mut blob).unwrap();
+    /// assert_eq!(t.blob_len(), n);
+    ///
+    /// let copy = BFRTABLE::<64, 8>::from_blob(&blob[..n]).unwrap();
+    /// assert_eq!(Some("two"), copy.find("two").and_then(|id| copy.get(id)));
+    /// ```
+    ///
+    pub fn write_blob(&self, out: &mut [u8]) -> Result<usize, err::NotEnoughCapacity> {
+        let total_len = self.blob_len();
+        if total_len > out.len() {
+            return Err(err::NotEnoughCapacity::throw(out.len(), total_len));
+        }
+        out[0..4].copy_from_slice(&(self.count as u32).to_le_bytes());
+        out[4..8].copy_from_slice(&(self.used as u32).to_le_bytes());
+        for (i, e) in self.entries[..self.count].iter().enumerate() {
+            let at = HEADER + i * ENTRY;
+            let len = if e.removed { e.len as u32 | REMOVED } else { e.len as u32 };
+            out[at..at + 4].copy_from_slice(&(e.start as u32).to_le_bytes());
+            out[at + 4..at + 8].copy_from_slice(&len.to_le_bytes());
+        }
+        let at = HEADER + self.count * ENTRY;
+        out[at..total_len].copy_from_slice(&self.arena[..self.used]);
+        Ok(total_len)
+    }
+
+    /// load a table from a blob made by [BFRTABLE::write_blob].
+    /// Every entry is checked (bounds, utf-8 and no overlap with another entry),
+    /// the error index is the position in the blob where the problem was found
+    /// # example
+    /// ```
+    /// use cbfr::table::BFRTABLE;
+    ///
+    /// let err = BFRTABLE::<64, 8>::from_blob(&[1, 0, 0]).unwrap_err();
+    /// assert_eq!(0, err.index());
+    ///
+    /// // "aé" at 1..4 and "é" at 2..4 share bytes, compact would corrupt them
+    /// let mut blob = vec![3, 0, 0, 0, 4, 0, 0, 0];
+    /// blob.extend([0, 0, 0, 0, 1, 0, 0, 0x80]);
+    /// blob.extend([1, 0, 0, 0, 3, 0, 0, 0]);
+    /// blob.extend([2, 0, 0, 0, 2, 0, 0, 0]);
+    /// blob.extend([b'Z', b'a', 0xC3, 0xA9]);
+    /// let err = BFRTABLE::<64, 8>::from_blob(&blob).unwrap_err();
+    /// assert_eq!(24, err.index());
+    /// ```
+    ///
+    pub fn from_blob(blob: &[u8]) -> Result<Self, err::InvalidFormat> {
+        let read = |at: usize| -> Result<usize, err::InvalidFormat> {
+            match blob.get(at..at + 4) {
+                Some(b) => Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize),
+                None => Err(err::InvalidFormat::throw(at, "unexpected end of blob")),
+            }
+        };
+        let count = read(0)?;
+        let used = read(4)?;
+        if count > ENTRIES {
+            return Err(err::InvalidFormat::throw(0, "too many entries"));
+        }
+        if used > BYTES {
+            return Err(err::InvalidFormat::throw(4, "arena too large"));
+        }
+        let arena_at = HEADER + count * ENTRY;
+        if blob.len() != arena_at + used {
+            return Err(err::InvalidFormat::throw(blob.len().min(arena_at + used), "blob len mismatch"));
+        }
+        let mut me = Self::new();
+        me.arena[..used].copy_from_slice(&blob[arena_at..]);
+        me.used = used;
+        for i in 0..count {
+            let at = HEADER + i * ENTRY;
+            let start = read(at)?;
+            let len = read(at + 4)?;
+            let removed = len & REMOVED as usize != 0;
+            let len = len & !(REMOVED as usize);
+            if start + len > used {
+                return Err(err::InvalidFormat::throw(at, "entry out of arena"));
+            }
+            if std::str::from_utf8(&me.arena[start..start + len]).is_err() {
+                return Err(err::InvalidFormat::throw(arena_at + start, "entry is not utf-8"));
+            }
+            if removed { me.removed += 1; }
+            me.entries[i] = Entry { start, len, removed };
+        }
+        // compact moves every entry once, so no two entries may share arena bytes
+        let mut order = [0usize; ENTRIES];
+        for (i, slot) in order[..count].iter_mut().enumerate() { *slot = i; }
+        let entries = &me.entries;
+        order[..count].sort_unstable_by_key(|i| (entries[*i].start, entries[*i].len));
+        let mut end = 0;
+        for &i in order[..count].iter() {
+            let e = &me.entries[i];
+            if e.len == 0 { continue; }
+            if e.start < end {
+                return Err(err::InvalidFormat::throw(HEADER + i * ENTRY, "entries overlap"));
+            }
+            end = e.start + e.len;
+        }
+        me.count = count;
+        me.sorted = (1..count).all(|i| me.text(&me.entries[i - 1]) <= me.text(&me.entries[i]));
+        Ok(me)
+    }
+}
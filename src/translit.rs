@@ -0,0 +1,145 @@
+//! ASCII transliteration and slugs.
+//! [fold] map Latin-1 Supplement and Latin Extended-A chars (U+00A0 to U+017F)
+//! to ASCII: é → e, ß → ss, Ø → O, Œ → OE, ½ → 1/2 ...
+//! Combining marks (U+0300 to U+036F) and control chars fold to nothing,
+//! so decomposed text like `"e\u{301}"` become `"e"` too.
+//! # example
+//! ```
+//! use cbfr::BFRDYN;
+//!
+//! let mut title: BFRDYN<64> = "Crème Brûlée & Smørrebrød: ½ price!".into();
+//! title.slugify('-').unwrap();
+//! assert_eq!("creme-brulee-smorrebrod-1-2-price", title.as_str());
+//! ```
+
+use crate::cb::{BFRDYN, NecResult};
+use crate::errors as err;
+
+/// first char covered by [TABLE]
+const TABLE_START: u32 = 0xA0;
+
+/// ASCII fold of U+00A0 to U+017F
+const TABLE: [&str; 224] = [
+    " ", "!", "c", "L", "", "Y", "|", "S", // 00A0  ¡¢£¤¥¦§
+    "", "(c)", "a", "<<", "!", "", "(r)", "", // 00A8 ¨©ª«¬ ®¯
+    "", "+-", "2", "3", "'", "u", "P", ".", // 00B0 °±²³´µ¶·
+    "", "1", "o", ">>", "1/4", "1/2", "3/4", "?", // 00B8 ¸¹º»¼½¾¿
+    "A", "A", "A", "A", "A", "A", "AE", "C", // 00C0 ÀÁÂÃÄÅÆÇ
+    "E", "E", "E", "E", "I", "I", "I", "I", // 00C8 ÈÉÊËÌÍÎÏ
+    "D", "N", "O", "O", "O", "O", "O", "x", // 00D0 ÐÑÒÓÔÕÖ×
+    "O", "U", "U", "U", "U", "Y", "TH", "ss", // 00D8 ØÙÚÛÜÝÞß
+    "a", "a", "a", "a", "a", "a", "ae", "c", // 00E0 àáâãäåæç
+    "e", "e", "e", "e", "i", "i", "i", "i", // 00E8 èéêëìíîï
+    "d", "n", "o", "o", "o", "o", "o", "/", // 00F0 ðñòóôõö÷
+    "o", "u", "u", "u", "u", "y", "th", "y", // 00F8 øùúûüýþÿ
+    "A", "a", "A", "a", "A", "a", "C", "c", // 0100 ĀāĂăĄąĆć
+    "C", "c", "C", "c", "C", "c", "D", "d", // 0108 ĈĉĊċČčĎď
+    "D", "d", "E", "e", "E", "e", "E", "e", // 0110 ĐđĒēĔĕĖė
+    "E", "e", "E", "e", "G", "g", "G", "g", // 0118 ĘęĚěĜĝĞğ
+    "G", "g", "G", "g", "H", "h", "H", "h", // 0120 ĠġĢģĤĥĦħ
+    "I", "i", "I", "i", "I", "i", "I", "i", // 0128 ĨĩĪīĬĭĮį
+    "I", "i", "IJ", "ij", "J", "j", "K", "k", // 0130 İıĲĳĴĵĶķ
+    "k", "L", "l", "L", "l", "L", "l", "L", // 0138 ĸĹĺĻļĽľĿ
+    "l", "L", "l", "N", "n", "N", "n", "N", // 0140 ŀŁłŃńŅņŇ
+    "n", "'n", "N", "n", "O", "o", "O", "o", // 0148 ňŉŊŋŌōŎŏ
+    "O", "o", "OE", "oe", "R", "r", "R", "r", // 0150 ŐőŒœŔŕŖŗ
+    "R", "r", "S", "s", "S", "s", "S", "s", // 0158 ŘřŚśŜŝŞş
+    "S", "s", "T", "t", "T", "t", "T", "t", // 0160 ŠšŢţŤťŦŧ
+    "U", "u", "U", "u", "U", "u", "U", "u", // 0168 ŨũŪūŬŭŮů
+    "U", "u", "U", "u", "W", "w", "Y", "y", // 0170 ŰűŲųŴŵŶŷ
+    "Y", "Z", "z", "Z", "z", "Z", "z", "s", // 0178 ŸŹźŻżŽžſ
+];
+
+/// ASCII replacement of a non ASCII char, None if there is none known.
+/// ASCII chars are returned as is by the functions using it, so they have no entry
+/// # example
+/// ```
+/// use cbfr::translit::fold;
+///
+/// assert_eq!(Some("ss"), fold('ß'));
+/// assert_eq!(Some("L"), fold('Ł'));
+/// assert_eq!(Some(""), fold('\u{301}'));
+/// assert_eq!(None, fold('a'));
+/// assert_eq!(None, fold('字'));
+/// ```
+///
+pub const fn fold(c: char) -> Option<&'static str> {
+    let c = c as u32;
+    if c < 0x80 { return None; }
+    if c < TABLE_START || (c >= 0x300 && c <= 0x36F) { return Some(""); }
+    if c < TABLE_START + TABLE.len() as u32 { return Some(TABLE[(c - TABLE_START) as usize]); }
+    None
+}
+
+impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
+    /// replace every non ASCII char by its [fold], chars with no known fold are removed.
+    /// The buffer is unchanged on error (a fold like ½ → 1/2 can grow the text)
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    ///
+    /// let mut b: BFRDYN<32> = "Øresund straße, Ĳssel 字".into();
+    /// b.transliterate_ascii().unwrap();
+    /// assert_eq!("Oresund strasse, IJssel ", b.as_str());
+    ///
+    /// let mut full: BFRDYN<4> = "½½".into();
+    /// assert!(full.transliterate_ascii().is_err());
+    /// assert_eq!("½½", full.as_str());
+    /// ```
+    ///
+    pub fn transliterate_ascii(&mut self) -> NecResult {
+        let mut out = Self::new();
+        let mut total = 0;
+        for c in self.as_str().chars() {
+            match fold(c) {
+                Some(ascii) => out.push_counted(&mut total, ascii.as_bytes()),
+                None if c.is_ascii() => out.push_counted(&mut total, &[c as u8]),
+                None => {},
+            }
+        }
+        if total > CAPACITY {
+            return Err(err::NotEnoughCapacity::throw(CAPACITY, total));
+        }
+        *self = out;
+        Ok(())
+    }
+
+    /// turn the buffer into a slug: lowercase, transliterated to ASCII, every run of
+    /// non alphanumeric chars replaced by one `sep`, no `sep` at either end.
+    /// The buffer is unchanged on error
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    ///
+    /// let mut b: BFRDYN<32> = "  Hello, Wörld!! -- 2024 ".into();
+    /// b.slugify('_').unwrap();
+    /// assert_eq!("hello_world_2024", b.as_str());
+    /// ```
+    ///
+    pub fn slugify(&mut self, sep: char) -> NecResult {
+        let mut out = Self::new();
+        let mut total = 0;
+        let mut pending = false;
+        let mut push = |byte: u8| {
+            if byte.is_ascii_alphanumeric() {
+                if pending && total > 0 { out.push_char_counted(&mut total, sep); }
+                pending = false;
+                out.push_counted(&mut total, &[byte.to_ascii_lowercase()]);
+            } else {
+                pending = true;
+            }
+        };
+        for c in self.as_str().chars() {
+            match fold(c) {
+                Some(ascii) => ascii.bytes().for_each(&mut push),
+                None if c.is_ascii() => push(c as u8),
+                None => push(b' '),
+            }
+        }
+        if total > CAPACITY {
+            return Err(err::NotEnoughCapacity::throw(CAPACITY, total));
+        }
+        *self = out;
+        Ok(())
+    }
+}
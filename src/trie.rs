@@ -0,0 +1,398 @@
+use crate::cb::{BFRDYN, DEFCAPACITY};
+use crate::errors as err;
+
+/// no node
+const NIL: u32 = u32::MAX;
+
+/// arena node, one byte of a key.
+/// Children are a linked list of siblings sorted by byte
+#[derive(Debug, Clone)]
+struct Node<V> {
+    byte: u8,
+    child: u32,
+    sibling: u32,
+    value: Option<V>,
+}
+
+impl<V> Node<V> {
+    const fn new(byte: u8) -> Self {
+        Self { byte, child: NIL, sibling: NIL, value: None }
+    }
+}
+
+/// BFRTRIE is a prefix tree of string keys stored in a fixed node arena,
+/// made for autocomplete and routing on short strings.
+/// - V is the value type
+/// - NODES is the capacity of the arena, a key use one node per byte
+///   but nodes are shared between keys with the same prefix
+/// - KEY is the maximum key length in bytes (capacity of the BFRDYN yielded by iterators)
+///
+/// Keys can be &str, BFRDYN or anything that implement `AsRef<str>`.
+/// # example
+/// ```
+/// use cbfr::trie::BFRTRIE;
+///
+/// let mut cmds: BFRTRIE<u8, 64, 16> = BFRTRIE::new();
+/// cmds.insert("status", 1).unwrap();
+/// cmds.insert("stash", 2).unwrap();
+/// cmds.insert("show", 3).unwrap();
+///
+/// let names: Vec<String> = cmds.iter_prefix("st").map(|(k, _)| k.to_string()).collect();
+/// assert_eq!(vec!["stash", "status"], names);
+/// assert_eq!(Some(("show", &3)), cmds.longest_prefix_match("show --stat"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct BFRTRIE<V, const NODES: usize = 256, const KEY: usize = DEFCAPACITY> {
+    nodes: [Node<V>; NODES],
+    used: usize,
+    free: u32,
+    free_count: usize,
+    len: usize,
+}
+
+impl<V, const NODES: usize, const KEY: usize> Default for BFRTRIE<V, NODES, KEY> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<V, const NODES: usize, const KEY: usize> BFRTRIE<V, NODES, KEY> {
+    /// create new empty trie, the first node of the arena is the root
+    /// # example
+    /// ```
+    /// use cbfr::trie::BFRTRIE;
+    ///
+    /// let t: BFRTRIE<u32> = BFRTRIE::new();
+    /// assert!(t.is_empty());
+    /// assert_eq!(1, t.nodes_used());
+    /// ```
+    ///
+    pub fn new() -> Self {
+        Self {
+            nodes: std::array::from_fn(|_| Node::new(0)),
+            used: if NODES == 0 { 0 } else { 1 },
+            free: NIL,
+            free_count: 0,
+            len: 0,
+        }
+    }
+
+    /// get the number of keys
+    pub const fn len(&self) -> usize { self.len }
+
+    /// check if trie has no key
+    pub const fn is_empty(&self) -> bool { self.len == 0 }
+
+    /// get the capacity of the node arena
+    pub const fn capacity(&self) -> usize { NODES }
+
+    /// get the number of nodes in use (root included)
+    pub const fn nodes_used(&self) -> usize { self.used - self.free_count }
+
+    /// find the child of a node holding a byte
+    fn child(&self, node: u32, byte: u8) -> Option<u32> {
+        let mut c = self.nodes[node as usize].child;
+        while c != NIL {
+            let n = &self.nodes[c as usize];
+            if n.byte == byte { return Some(c); }
+            if n.byte > byte { return None; }
+            c = n.sibling;
+        }
+        None
+    }
+
+    /// walk down the trie, return the deepest node and how many bytes were matched
+    fn walk(&self, key: &[u8]) -> (u32, usize) {
+        let mut node = 0;
+        for (i, b) in key.iter().enumerate() {
+            match self.child(node, *b) {
+                Some(c) => node = c,
+                None => return (node, i),
+            }
+        }
+        (node, key.len())
+    }
+
+    fn find(&self, key: &[u8]) -> Option<u32> {
+        if NODES == 0 { return None; }
+        match self.walk(key) {
+            (node, n) if n == key.len() => Some(node),
+            _ => None,
+        }
+    }
+
+    fn alloc(&mut self, byte: u8) -> u32 {
+        let i = if self.free != NIL {
+            let i = self.free;
+            self.free = self.nodes[i as usize].sibling;
+            self.free_count -= 1;
+            i
+        } else {
+            self.used += 1;
+            (self.used - 1) as u32
+        };
+        self.nodes[i as usize] = Node::new(byte);
+        i
+    }
+
+    fn release(&mut self, node: u32) {
+        self.nodes[node as usize].sibling = self.free;
+        self.free = node;
+        self.free_count += 1;
+    }
+
+    /// add a child holding a byte, keeping siblings sorted
+    fn add_child(&mut self, node: u32, byte: u8) -> u32 {
+        let new = self.alloc(byte);
+        let mut prev = NIL;
+        let mut c = self.nodes[node as usize].child;
+        while c != NIL && self.nodes[c as usize].byte < byte {
+            prev = c;
+            c = self.nodes[c as usize].sibling;
+        }
+        self.nodes[new as usize].sibling = c;
+        if prev == NIL {
+            self.nodes[node as usize].child = new;
+        } else {
+            self.nodes[prev as usize].sibling = new;
+        }
+        new
+    }
+
+    /// insert a key, return the old value if the key was already there.
+    /// Return NotEnoughCapacity when the key is longer than KEY bytes
+    /// or the arena doesn't have enough free nodes, the trie is unchanged then
+    /// # example
+    /// ```
+    /// use cbfr::trie::BFRTRIE;
+    ///
+    /// let mut t: BFRTRIE<i32, 6, 8> = BFRTRIE::new();
+    /// assert_eq!(None, t.insert("abc", 1).unwrap());
+    /// assert_eq!(Some(1), t.insert("abc", 2).unwrap());
+    /// t.insert("abd", 3).unwrap();       // share "ab"
+    /// assert!(t.insert("xyz", 4).is_err());  // 3 nodes needed, 1 left
+    /// assert!(t.insert("too long key", 5).is_err());
+    /// assert_eq!(2, t.len());
+    /// ```
+    ///
+    pub fn insert(&mut self, key: impl AsRef<str>, value: V) -> Result<Option<V>, err::NotEnoughCapacity> {
+        let key = key.as_ref().as_bytes();
+        if key.len() > KEY { return Err(err::NotEnoughCapacity::throw(KEY, key.len())); }
+        if NODES == 0 { return Err(err::NotEnoughCapacity::throw(NODES, 1)); }
+        let (mut node, matched) = self.walk(key);
+        let needed = key.len() - matched;
+        let available = NODES - self.used + self.free_count;
+        if needed > available {
+            return Err(err::NotEnoughCapacity::throw(NODES, self.nodes_used() + needed));
+        }
+        for b in &key[matched..] {
+            node = self.add_child(node, *b);
+        }
+        let old = self.nodes[node as usize].value.replace(value);
+        if old.is_none() { self.len += 1; }
+        Ok(old)
+    }
+
+    /// get a reference to the value of a key
+    pub fn get(&self, key: impl AsRef<str>) -> Option<&V> {
+        let node = self.find(key.as_ref().as_bytes())?;
+        self.nodes[node as usize].value.as_ref()
+    }
+
+    /// get a mutable reference to the value of a key
+    /// # example
+    /// ```
+    /// use cbfr::trie::BFRTRIE;
+    /// use cbfr::BFRDYN;
+    ///
+    /// let mut t: BFRTRIE<u32> = BFRTRIE::new();
+    /// let key: BFRDYN<16> = "count".into();
+    /// t.insert(&key, 1).unwrap();
+    /// *t.get_mut(&key).unwrap() += 1;
+    /// assert_eq!(Some(&2), t.get("count"));
+    /// ```
+    ///
+    pub fn get_mut(&mut self, key: impl AsRef<str>) -> Option<&mut V> {
+        let node = self.find(key.as_ref().as_bytes())?;
+        self.nodes[node as usize].value.as_mut()
+    }
+
+    /// check if the trie has a key
+    pub fn contains_key(&self, key: impl AsRef<str>) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// remove value under `key` below `node`, unlinking nodes that lead nowhere
+    fn remove_below(&mut self, node: u32, key: &[u8]) -> Option<V> {
+        let Some((&b, rest)) = key.split_first() else {
+            return self.nodes[node as usize].value.take();
+        };
+        let mut prev = NIL;
+        let mut c = self.nodes[node as usize].child;
+        while c != NIL && self.nodes[c as usize].byte != b {
+            prev = c;
+            c = self.nodes[c as usize].sibling;
+        }
+        if c == NIL { return None; }
+        let value = self.remove_below(c, rest)?;
+        let dead = &self.nodes[c as usize];
+        if dead.value.is_none() && dead.child == NIL {
+            let next = dead.sibling;
+            if prev == NIL {
+                self.nodes[node as usize].child = next;
+            } else {
+                self.nodes[prev as usize].sibling = next;
+            }
+            self.release(c);
+        }
+        Some(value)
+    }
+
+    /// remove a key and return its value, nodes only used by this key
+    /// go back to the arena
+    /// # example
+    /// ```
+    /// use cbfr::trie::BFRTRIE;
+    ///
+    /// let mut t: BFRTRIE<u8, 16> = BFRTRIE::new();
+    /// t.insert("car", 1).unwrap();
+    /// t.insert("cart", 2).unwrap();
+    /// assert_eq!(5, t.nodes_used());
+    ///
+    /// assert_eq!(Some(2), t.remove("cart"));
+    /// assert_eq!(4, t.nodes_used());
+    /// assert_eq!(None, t.remove("ca"));
+    /// assert_eq!(Some(&1), t.get("car"));
+    /// ```
+    ///
+    pub fn remove(&mut self, key: impl AsRef<str>) -> Option<V> {
+        if NODES == 0 { return None; }
+        let value = self.remove_below(0, key.as_ref().as_bytes())?;
+        self.len -= 1;
+        Some(value)
+    }
+
+    /// find the longest key that is a prefix of `text`,
+    /// return the matching part of `text` and the value
+    /// # example
+    /// ```
+    /// use cbfr::trie::BFRTRIE;
+    ///
+    /// let mut routes: BFRTRIE<&str> = BFRTRIE::new();
+    /// routes.insert("/", "root").unwrap();
+    /// routes.insert("/api", "api").unwrap();
+    /// routes.insert("/api/users", "users").unwrap();
+    ///
+    /// assert_eq!(Some(("/api/users", &"users")), routes.longest_prefix_match("/api/users/42"));
+    /// assert_eq!(Some(("/api", &"api")), routes.longest_prefix_match("/api/orders"));
+    /// assert_eq!(Some(("/", &"root")), routes.longest_prefix_match("/index.html"));
+    /// assert_eq!(None, routes.longest_prefix_match("api"));
+    /// ```
+    ///
+    pub fn longest_prefix_match<'t>(&self, text: &'t str) -> Option<(&'t str, &V)> {
+        if NODES == 0 { return None; }
+        let mut node = 0;
+        let mut best = self.nodes[0].value.as_ref().map(|v| (0, v));
+        for (i, b) in text.as_bytes().iter().enumerate() {
+            match self.child(node, *b) {
+                Some(c) => node = c,
+                None => break,
+            }
+            if let Some(v) = &self.nodes[node as usize].value {
+                best = Some((i + 1, v));
+            }
+        }
+        best.map(|(n, v)| (&text[..n], v))
+    }
+
+    /// iterate over every (key, &value) starting with `prefix`, sorted by key bytes
+    /// # example
+    /// ```
+    /// use cbfr::trie::BFRTRIE;
+    ///
+    /// let mut t: BFRTRIE<usize, 64, 16> = BFRTRIE::new();
+    /// for (i, w) in ["tea", "ten", "to", "te", "inn"].iter().enumerate() {
+    ///     t.insert(w, i).unwrap();
+    /// }
+    /// let te: Vec<(String, usize)> = t.iter_prefix("te")
+    ///     .map(|(k, v)| (k.to_string(), *v))
+    ///     .collect();
+    /// assert_eq!(vec![("te".to_string(), 3), ("tea".to_string(), 0), ("ten".to_string(), 1)], te);
+    /// assert_eq!(0, t.iter_prefix("x").count());
+    /// ```
+    ///
+    pub fn iter_prefix(&self, prefix: impl AsRef<str>) -> PrefixIter<'_, V, NODES, KEY> {
+        let prefix = prefix.as_ref().as_bytes();
+        let mut it = PrefixIter {
+            nodes: &self.nodes,
+            buf: [0; KEY],
+            base: prefix.len(),
+            depth: 0,
+            path: [NIL; KEY],
+            cur: NIL,
+            start: None,
+        };
+        if prefix.len() <= KEY {
+            if let Some(node) = self.find(prefix) {
+                it.buf[..prefix.len()].copy_from_slice(prefix);
+                it.start = Some(node);
+            }
+        }
+        it
+    }
+
+    /// iterate over every (key, &value) sorted by key bytes
+    pub fn iter(&self) -> PrefixIter<'_, V, NODES, KEY> { self.iter_prefix("") }
+
+    /// remove every keys
+    pub fn clear(&mut self) {
+        for n in self.nodes.iter_mut() { *n = Node::new(0); }
+        self.used = if NODES == 0 { 0 } else { 1 };
+        self.free = NIL;
+        self.free_count = 0;
+        self.len = 0;
+    }
+}
+
+/// Iterator returned by [BFRTRIE::iter_prefix], depth first in byte order
+pub struct PrefixIter<'a, V, const NODES: usize, const KEY: usize> {
+    nodes: &'a [Node<V>; NODES],
+    buf: [u8; KEY],
+    base: usize,
+    depth: usize,
+    path: [u32; KEY],
+    cur: u32,
+    start: Option<u32>,
+}
+
+impl<'a, V, const NODES: usize, const KEY: usize> PrefixIter<'a, V, NODES, KEY> {
+    fn key(&self) -> BFRDYN<KEY> {
+        let key = std::str::from_utf8(&self.buf[..self.base + self.depth])
+            .expect("keys are inserted as str");
+        BFRDYN::from(key)
+    }
+}
+
+impl<'a, V, const NODES: usize, const KEY: usize> Iterator for PrefixIter<'a, V, NODES, KEY> {
+    type Item = (BFRDYN<KEY>, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(start) = self.start.take() {
+            let node = &self.nodes[start as usize];
+            self.cur = node.child;
+            if let Some(v) = &node.value { return Some((self.key(), v)); }
+        }
+        loop {
+            if self.cur != NIL {
+                let node = &self.nodes[self.cur as usize];
+                self.path[self.depth] = self.cur;
+                self.buf[self.base + self.depth] = node.byte;
+                self.depth += 1;
+                self.cur = node.child;
+                if let Some(v) = &node.value { return Some((self.key(), v)); }
+            } else {
+                if self.depth == 0 { return None; }
+                self.depth -= 1;
+                self.cur = self.nodes[self.path[self.depth] as usize].sibling;
+            }
+        }
+    }
+}
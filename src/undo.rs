@@ -0,0 +1,465 @@
+use std::ops::Deref;
+
+use crate::cb::{BFRDYN, DEFCAPACITY, NecResult, IidxResult};
+use crate::errors as err;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum OpKind {
+    #[default]
+    Insert,
+    Delete,
+}
+
+/// one recorded edit, the bytes live in the journal text log at start..start+len
+#[derive(Debug, Clone, Copy, Default)]
+struct Op {
+    kind: OpKind,
+    pos: usize,
+    start: usize,
+    len: usize,
+    /// undone and redone together with the previous op
+    joined: bool,
+    /// no more keystroke can be merged into this op
+    sealed: bool,
+}
+
+/// BFRUNDO wraps a BFRDYN and records every edit into a fixed capacity journal
+/// so it can be undone and redone. Nothing is allocated on the heap.
+/// - STEPS is the maximum number of recorded edits
+/// - LOG is the number of bytes available to store inserted and deleted text
+///
+/// When the journal is full the oldest edits are forgotten.
+/// Consecutive keystrokes (single char typed or deleted next to the previous one)
+/// are grouped into one step, call [BFRUNDO::seal] to start a new step.
+/// Reading methods of BFRDYN are available through Deref.
+/// # example
+/// ```
+/// use cbfr::undo::BFRUNDO;
+///
+/// let mut u: BFRUNDO<64> = BFRUNDO::new();
+/// for c in "hello".chars() { u.append_ch(c).unwrap(); }
+/// u.append_str(" world").unwrap();
+///
+/// u.undo();
+/// assert_eq!("hello", u.as_str());
+/// u.undo();
+/// assert_eq!("", u.as_str());
+/// u.redo();
+/// assert_eq!("hello", u.as_str());
+/// ```
+#[derive(Debug, Clone)]
+pub struct BFRUNDO<const CAPACITY: usize = DEFCAPACITY, const STEPS: usize = 64, const LOG: usize = 1024> {
+    buf: BFRDYN<CAPACITY>,
+    ops: [Op; STEPS],
+    count: usize,
+    cursor: usize,
+    log: [u8; LOG],
+}
+
+impl<const CAPACITY: usize, const STEPS: usize, const LOG: usize> Deref for BFRUNDO<CAPACITY, STEPS, LOG> {
+    type Target = BFRDYN<CAPACITY>;
+    fn deref(&self) -> &Self::Target { &self.buf }
+}
+
+/// Create journal from an existing buffer, the initial value can not be undone
+/// # example
+/// ```
+/// use cbfr::undo::BFRUNDO;
+/// use cbfr::BFRDYN;
+///
+/// let b: BFRDYN<64> = "initial".into();
+/// let mut u: BFRUNDO<64> = b.into();
+/// assert!(!u.undo());
+/// assert_eq!("initial", u.as_str());
+/// ```
+///
+impl<const CAPACITY: usize, const STEPS: usize, const LOG: usize> From<BFRDYN<CAPACITY>> for BFRUNDO<CAPACITY, STEPS, LOG> {
+    fn from(value: BFRDYN<CAPACITY>) -> Self {
+        let mut me = Self::new();
+        me.buf = value;
+        me
+    }
+}
+
+impl<const CAPACITY: usize, const STEPS: usize, const LOG: usize> From<&str> for BFRUNDO<CAPACITY, STEPS, LOG> {
+    fn from(value: &str) -> Self { BFRDYN::from(value).into() }
+}
+
+impl<const CAPACITY: usize, const STEPS: usize, const LOG: usize> Default for BFRUNDO<CAPACITY, STEPS, LOG> {
+    fn default() -> Self { Self::new() }
+}
+
+/// true if bytes hold exactly one char (a keystroke)
+fn is_keystroke(bytes: &[u8]) -> bool {
+    match std::str::from_utf8(bytes) {
+        Ok(s) => s.chars().count() == 1,
+        Err(_) => bytes.len() == 1,
+    }
+}
+
+impl<const CAPACITY: usize, const STEPS: usize, const LOG: usize> BFRUNDO<CAPACITY, STEPS, LOG> {
+    /// create new empty journal
+    pub const fn new() -> Self {
+        Self {
+            buf: BFRDYN::new(),
+            ops: [Op { kind: OpKind::Insert, pos: 0, start: 0, len: 0, joined: false, sealed: false }; STEPS],
+            count: 0,
+            cursor: 0,
+            log: [0u8; LOG],
+        }
+    }
+
+    /// get the wrapped buffer
+    pub const fn as_bfrdyn(&self) -> &BFRDYN<CAPACITY> { &self.buf }
+
+    /// take the wrapped buffer and drop the journal
+    pub fn into_inner(self) -> BFRDYN<CAPACITY> { self.buf }
+
+    /// check if there is an edit to undo
+    pub const fn can_undo(&self) -> bool { self.cursor > 0 }
+
+    /// check if there is an edit to redo
+    pub const fn can_redo(&self) -> bool { self.cursor < self.count }
+
+    /// forget every recorded edit, the buffer is left untouched
+    pub fn clear_history(&mut self) {
+        self.count = 0;
+        self.cursor = 0;
+    }
+
+    /// end the current group of keystrokes, the next edit start a new undo step
+    /// # example
+    /// ```
+    /// use cbfr::undo::BFRUNDO;
+    ///
+    /// let mut u: BFRUNDO<64> = BFRUNDO::new();
+    /// u.append_ch('a').unwrap();
+    /// u.seal();
+    /// u.append_ch('b').unwrap();
+    ///
+    /// u.undo();
+    /// assert_eq!("a", u.as_str());
+    /// ```
+    ///
+    pub fn seal(&mut self) {
+        if self.cursor > 0 { self.ops[self.cursor - 1].sealed = true; }
+    }
+
+    /// end of the text log used by applied edits
+    fn log_end(&self) -> usize {
+        if self.cursor == 0 { 0 } else {
+            let op = self.ops[self.cursor - 1];
+            op.start + op.len
+        }
+    }
+
+    /// forget the oldest edit (and the edits joined to it)
+    fn drop_oldest(&mut self) {
+        let mut n = 1;
+        while n < self.count && self.ops[n].joined { n += 1; }
+        let end = self.log_end();
+        let bytes = if n < self.count { self.ops[n].start } else { end };
+        self.log.copy_within(bytes..end, 0);
+        self.ops.copy_within(n..self.count, 0);
+        self.count -= n;
+        self.cursor -= n;
+        for op in self.ops[..self.count].iter_mut() { op.start -= bytes; }
+    }
+
+    /// try to merge a keystroke into the last applied op
+    fn merge(&mut self, kind: OpKind, pos: usize, bytes: &[u8]) -> bool {
+        if self.cursor == 0 || !is_keystroke(bytes) { return false; }
+        let end = self.log_end();
+        if end + bytes.len() > LOG { return false; }
+        let last = &mut self.ops[self.cursor - 1];
+        if last.sealed || last.kind != kind { return false; }
+        let n = bytes.len();
+        match kind {
+            OpKind::Insert if pos == last.pos + last.len => {
+                self.log[end..end + n].copy_from_slice(bytes);
+            },
+            OpKind::Delete if pos + n == last.pos => {
+                self.log[end..end + n].copy_from_slice(bytes);
+                self.log[last.start..end + n].rotate_right(n);
+                last.pos = pos;
+            },
+            OpKind::Delete if pos == last.pos => {
+                self.log[end..end + n].copy_from_slice(bytes);
+            },
+            _ => return false,
+        }
+        last.len += n;
+        true
+    }
+
+    /// record an edit that is about to be applied
+    fn record(&mut self, kind: OpKind, pos: usize, bytes: &[u8], mut joined: bool) {
+        self.count = self.cursor;
+        if !joined && self.merge(kind, pos, bytes) { return; }
+        if STEPS == 0 || bytes.len() > LOG {
+            // edit is too large to be recorded, it can not be undone
+            // and the edits before it can not be replayed anymore
+            self.clear_history();
+            return;
+        }
+        while self.count == STEPS || self.log_end() + bytes.len() > LOG {
+            self.drop_oldest();
+        }
+        if self.count == 0 { joined = false; }
+        let start = self.log_end();
+        self.log[start..start + bytes.len()].copy_from_slice(bytes);
+        self.ops[self.count] = Op { kind, pos, start, len: bytes.len(), joined, sealed: !is_keystroke(bytes) };
+        self.count += 1;
+        self.cursor = self.count;
+    }
+
+    fn insert_recorded(&mut self, pos: usize, bytes: &[u8], joined: bool) -> NecResult {
+        let total_len = self.buf.len() + bytes.len();
+        if total_len > CAPACITY {
+            return Err(err::NotEnoughCapacity::throw(CAPACITY, total_len));
+        }
+        // also reject a position inside a multi bytes char
+        if !self.buf.as_str().is_char_boundary(pos) {
+            return Err(err::InvalidIndex::throw(self.buf.len(), pos).into());
+        }
+        if bytes.is_empty() { return Ok(()); }
+        self.record(OpKind::Insert, pos, bytes, joined);
+        self.buf.insert_bytes(pos, bytes);
+        Ok(())
+    }
+
+    fn delete_recorded(&mut self, start: usize, end: usize, joined: bool) -> IidxResult {
+        let text = self.buf.as_str();
+        for pos in [start, end] {
+            if !text.is_char_boundary(pos) {
+                return Err(err::InvalidIndex::throw(self.buf.len(), pos));
+            }
+        }
+        if start >= end { return Ok(()); }
+        let arr = self.buf.arr;
+        self.record(OpKind::Delete, start, &arr[start..end], joined);
+        self.buf.remove_bytes(start, end);
+        Ok(())
+    }
+
+    /// apply op backward
+    fn revert(&mut self, op: Op) {
+        match op.kind {
+            OpKind::Insert => self.buf.remove_bytes(op.pos, op.pos + op.len),
+            OpKind::Delete => self.buf.insert_bytes(op.pos, &self.log[op.start..op.start + op.len]),
+        }
+    }
+
+    /// apply op forward
+    fn replay(&mut self, op: Op) {
+        match op.kind {
+            OpKind::Insert => self.buf.insert_bytes(op.pos, &self.log[op.start..op.start + op.len]),
+            OpKind::Delete => self.buf.remove_bytes(op.pos, op.pos + op.len),
+        }
+    }
+
+    /// undo the last step, return false if there is nothing to undo
+    /// # example
+    /// ```
+    /// use cbfr::undo::BFRUNDO;
+    ///
+    /// let mut u: BFRUNDO<64> = "some text".into();
+    /// u.popn(5).unwrap();
+    /// u.append_str(" thing").unwrap();
+    ///
+    /// assert!(u.undo());
+    /// assert_eq!("some", u.as_str());
+    /// assert!(u.undo());
+    /// assert_eq!("some text", u.as_str());
+    /// assert!(!u.undo());
+    /// ```
+    ///
+    pub fn undo(&mut self) -> bool {
+        if self.cursor == 0 { return false; }
+        loop {
+            self.cursor -= 1;
+            let op = self.ops[self.cursor];
+            self.revert(op);
+            if !op.joined || self.cursor == 0 { break; }
+        }
+        self.seal();
+        true
+    }
+
+    /// redo the last undone step, return false if there is nothing to redo.
+    /// Any new edit after undo discard the steps that could be redone
+    /// # example
+    /// ```
+    /// use cbfr::undo::BFRUNDO;
+    ///
+    /// let mut u: BFRUNDO<64> = "abc".into();
+    /// u.take(1).unwrap();
+    /// u.undo();
+    /// assert!(u.redo());
+    /// assert_eq!("ac", u.as_str());
+    ///
+    /// u.undo();
+    /// u.append_ch('d').unwrap();
+    /// assert!(!u.redo());
+    /// assert_eq!("abcd", u.as_str());
+    /// ```
+    ///
+    pub fn redo(&mut self) -> bool {
+        if self.cursor == self.count { return false; }
+        loop {
+            let op = self.ops[self.cursor];
+            self.replay(op);
+            self.cursor += 1;
+            if self.cursor == self.count || !self.ops[self.cursor].joined { break; }
+        }
+        self.seal();
+        true
+    }
+
+    /// copy current value, use [BFRUNDO::restore] to go back to it
+    pub fn snapshot(&self) -> BFRDYN<CAPACITY> { self.buf.clone() }
+
+    /// replace the value with a snapshot, this is recorded as one undo step
+    /// # example
+    /// ```
+    /// use cbfr::undo::BFRUNDO;
+    ///
+    /// let mut u: BFRUNDO<64> = "draft".into();
+    /// let snap = u.snapshot();
+    /// u.clear();
+    /// u.append_str("changed").unwrap();
+    ///
+    /// u.restore(&snap);
+    /// assert_eq!("draft", u.as_str());
+    /// u.undo();
+    /// assert_eq!("changed", u.as_str());
+    /// ```
+    ///
+    pub fn restore(&mut self, snapshot: &BFRDYN<CAPACITY>) {
+        self.seal();
+        let deleted = !self.buf.is_empty();
+        // whole buffer, can't fail
+        let _ = self.delete_recorded(0, self.buf.len(), false);
+        let joined = deleted && self.cursor > 0;
+        // safety: unwrap() never fail, buffer is empty and snapshot has the same capacity
+        self.insert_recorded(0, snapshot.as_ref(), joined).unwrap();
+        self.seal();
+    }
+
+    /// append &str, see BFRDYN::append_str
+    pub fn append_str(&mut self, text: &str) -> NecResult {
+        self.insert_recorded(self.buf.len(), text.as_bytes(), false)
+    }
+
+    /// append a char, see BFRDYN::append_ch
+    pub fn append_ch(&mut self, c: char) -> NecResult {
+        self.append_str(c.encode_utf8(&mut [0u8; 4]))
+    }
+
+    /// insert &str at byte position `pos` (pos can be equal to len),
+    /// a position inside a multi bytes char is rejected
+    /// # example
+    /// ```
+    /// use cbfr::undo::BFRUNDO;
+    ///
+    /// let mut u: BFRUNDO<64> = "Amng".into();
+    /// u.insert_str("azi", 2).unwrap();
+    /// assert_eq!("Amazing", u.as_str());
+    /// u.undo();
+    /// assert_eq!("Amng", u.as_str());
+    ///
+    /// let mut e: BFRUNDO<16> = "é".into();
+    /// assert!(e.insert_str("x", 1).is_err());
+    /// assert_eq!("é", e.as_str());
+    /// ```
+    ///
+    pub fn insert_str(&mut self, text: &str, pos: usize) -> NecResult {
+        self.insert_recorded(pos, text.as_bytes(), false)
+    }
+
+    /// insert another buffer at a given position, see BFRDYN::insert
+    pub fn insert(&mut self, other: BFRDYN<CAPACITY>, pos: usize) -> NecResult {
+        self.insert_recorded(pos, other.as_ref(), false)
+    }
+
+    /// remove bytes in start..end, both ends must be on a char boundary
+    /// # example
+    /// ```
+    /// use cbfr::undo::BFRUNDO;
+    ///
+    /// let mut u: BFRUNDO<16> = "aé!".into();
+    /// assert!(u.remove_range(0, 2).is_err());
+    /// u.remove_range(1, 3).unwrap();
+    /// assert_eq!("a!", u.as_str());
+    /// ```
+    ///
+    pub fn remove_range(&mut self, start: usize, end: usize) -> IidxResult {
+        if start > end || end > self.buf.len() {
+            return Err(err::InvalidIndex::throw(self.buf.len(), end));
+        }
+        self.delete_recorded(start, end, false)
+    }
+
+    /// take and remove a byte, see BFRDYN::take.
+    /// Return None if the byte is part of a multi bytes char
+    /// # example
+    /// ```
+    /// use cbfr::undo::BFRUNDO;
+    ///
+    /// let mut u: BFRUNDO<16> = "éa".into();
+    /// assert_eq!(None, u.take(0));
+    /// assert_eq!(Some(b'a'), u.take(2));
+    /// assert_eq!("é", u.as_str());
+    /// ```
+    ///
+    pub fn take(&mut self, pos: usize) -> Option<u8> {
+        let value = self.buf.get(pos).copied()?;
+        self.delete_recorded(pos, pos + 1, false).ok()?;
+        Some(value)
+    }
+
+    /// pop the last char (multi bytes char is removed as a whole)
+    /// # example
+    /// ```
+    /// use cbfr::undo::BFRUNDO;
+    ///
+    /// let mut u: BFRUNDO<64> = "abç".into();
+    /// assert_eq!(Some('ç'), u.pop());
+    /// assert_eq!(Some('b'), u.pop());
+    /// u.undo();   // both backspace are one step
+    /// assert_eq!("abç", u.as_str());
+    /// ```
+    ///
+    pub fn pop(&mut self) -> Option<char> {
+        let c = self.buf.as_str().chars().next_back()?;
+        let len = self.buf.len();
+        self.delete_recorded(len - c.len_utf8(), len, false).ok()?;
+        Some(c)
+    }
+
+    /// pop n bytes, see BFRDYN::popn.
+    /// Return None if that would split a multi bytes char
+    /// # example
+    /// ```
+    /// use cbfr::undo::BFRUNDO;
+    ///
+    /// let mut u: BFRUNDO<16> = "aé".into();
+    /// assert!(u.popn(1).is_none());
+    /// assert_eq!("é", u.popn(2).unwrap().as_str());
+    /// assert_eq!("a", u.as_str());
+    /// ```
+    ///
+    pub fn popn(&mut self, n: usize) -> Option<BFRDYN<CAPACITY>> {
+        let len = self.buf.len();
+        if n > len || !self.buf.as_str().is_char_boundary(len - n) { return None; }
+        let popped: BFRDYN<CAPACITY> = self.buf.arr[len - n..len].into();
+        self.delete_recorded(len - n, len, false).ok()?;
+        Some(popped)
+    }
+
+    /// remove all data, see BFRDYN::clear
+    pub fn clear(&mut self) {
+        // whole buffer, can't fail
+        let _ = self.delete_recorded(0, self.buf.len(), false);
+    }
+}
@@ -0,0 +1,271 @@
+//! Percent encoding and `application/x-www-form-urlencoded` query strings, without allocation.
+//! Encode sets follow the WHATWG URL standard, every non ASCII byte is always encoded.
+//! # example
+//! ```
+//! use cbfr::BFRDYN;
+//! use cbfr::url::{self, QueryBuilder};
+//!
+//! let mut q: QueryBuilder<64> = QueryBuilder::new();
+//! q.append_pair("q", "café & crème").unwrap();
+//! q.append_pair("page", "2").unwrap();
+//! assert_eq!("q=caf%C3%A9+%26+cr%C3%A8me&page=2", q.as_str());
+//!
+//! let pairs: Vec<(BFRDYN<32>, BFRDYN<32>)> = url::query_pairs(q.as_str()).map(Result::unwrap).collect();
+//! assert_eq!("café & crème", pairs[0].1.as_str());
+//! assert_eq!(("page", "2"), (pairs[1].0.as_str(), pairs[1].1.as_str()));
+//! ```
+
+use std::fmt::{self, Write};
+
+use crate::cb::{BFRDYN, NecResult};
+use crate::errors as err;
+
+/// Set of ASCII bytes to percent encode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EncodeSet {
+    mask: u128,
+}
+
+impl EncodeSet {
+    /// set with the given bytes (must be ASCII)
+    pub const fn new(bytes: &[u8]) -> Self {
+        Self::EMPTY.add(bytes)
+    }
+
+    /// nothing but non ASCII bytes is encoded
+    pub const EMPTY: Self = Self { mask: 0 };
+
+    /// this set plus the given bytes (must be ASCII)
+    pub const fn add(mut self, bytes: &[u8]) -> Self {
+        let mut i = 0;
+        while i < bytes.len() {
+            self.mask |= 1 << bytes[i];
+            i += 1;
+        }
+        self
+    }
+
+    /// check if a byte must be encoded
+    pub const fn contains(&self, b: u8) -> bool {
+        b >= 0x80 || self.mask & (1 << b) != 0
+    }
+}
+
+/// C0 controls and DEL
+pub const CONTROLS: EncodeSet = EncodeSet { mask: 0xffff_ffff | 1 << 0x7f };
+/// fragment percent-encode set
+pub const FRAGMENT: EncodeSet = CONTROLS.add(b" \"<>`");
+/// query percent-encode set
+pub const QUERY: EncodeSet = CONTROLS.add(b" \"#<>");
+/// path percent-encode set
+pub const PATH: EncodeSet = QUERY.add(b"?`{}");
+/// userinfo percent-encode set
+pub const USERINFO: EncodeSet = PATH.add(b"/:;=@[\\]^|");
+/// component percent-encode set (like JavaScript encodeURIComponent)
+pub const COMPONENT: EncodeSet = USERINFO.add(b"$%&+,");
+/// application/x-www-form-urlencoded set, space is written as `+`
+pub const FORM: EncodeSet = COMPONENT.add(b"!'()~");
+
+const HEX: &[u8; 16] = b"0123456789ABCDEF";
+
+/// percent encode `s` into `out`, with `+` for space when `plus`
+fn encode(s: &str, set: &EncodeSet, plus: bool, out: &mut impl Write) -> fmt::Result {
+    let mut start = 0;
+    for (i, b) in s.bytes().enumerate() {
+        if !set.contains(b) { continue; }
+        // runs of kept bytes are ASCII, so they start and end on char boundaries
+        if start < i { out.write_str(&s[start..i])?; }
+        if plus && b == b' ' {
+            out.write_char('+')?;
+        } else {
+            out.write_char('%')?;
+            out.write_char(HEX[(b >> 4) as usize] as char)?;
+            out.write_char(HEX[(b & 15) as usize] as char)?;
+        }
+        start = i + 1;
+    }
+    out.write_str(&s[start..])
+}
+
+/// bytes written by encode
+fn encoded_len(s: &str, set: &EncodeSet, plus: bool) -> usize {
+    s.bytes().map(|b| if !set.contains(b) || (plus && b == b' ') { 1 } else { 3 }).sum()
+}
+
+/// percent decode `s` into `out`, `+` as space when `plus`.
+/// Error positions are shifted by `offset`
+fn decode(s: &str, offset: usize, plus: bool, out: &mut impl Write) -> Result<(), err::InvalidFormat> {
+    let bytes = s.as_bytes();
+    // a utf-8 sequence being rebuilt from escapes, with the position of its first byte
+    let mut seq = [0u8; 4];
+    let mut seq_len = 0;
+    let mut seq_at = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        let at = offset + i;
+        let b = match bytes[i] {
+            b'%' => {
+                let hi = bytes.get(i + 1).and_then(|h| (*h as char).to_digit(16));
+                let lo = bytes.get(i + 2).and_then(|l| (*l as char).to_digit(16));
+                let (Some(hi), Some(lo)) = (hi, lo) else {
+                    return Err(err::InvalidFormat::throw(at, "invalid percent escape"));
+                };
+                i += 3;
+                (hi * 16 + lo) as u8
+            },
+            b'+' if plus => { i += 1; b' ' },
+            b => { i += 1; b },
+        };
+        if seq_len == 0 { seq_at = at; }
+        seq[seq_len] = b;
+        seq_len += 1;
+        let want = crate::helper::utf8_width(seq[0]);
+        if seq_len < want { continue; }
+        let text = std::str::from_utf8(&seq[..seq_len])
+            .map_err(|_| err::InvalidFormat::throw(seq_at, "invalid utf-8"))?;
+        out.write_str(text).map_err(|_| err::InvalidFormat::throw(seq_at, "output is full"))?;
+        seq_len = 0;
+    }
+    if seq_len > 0 {
+        return Err(err::InvalidFormat::throw(seq_at, "invalid utf-8"));
+    }
+    Ok(())
+}
+
+impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
+    /// percent encode the buffer with an encode set ([PATH], [QUERY], [USERINFO], [COMPONENT] ...)
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    /// use cbfr::url;
+    ///
+    /// let b: BFRDYN<32> = "/docs/a b?x=1&y".into();
+    /// let mut path: BFRDYN<64> = BFRDYN::new();
+    /// b.percent_encode(&url::PATH, &mut path).unwrap();
+    /// assert_eq!("/docs/a%20b%3Fx=1&y", path.as_str());
+    ///
+    /// let mut component = String::new();
+    /// b.percent_encode(&url::COMPONENT, &mut component).unwrap();
+    /// assert_eq!("%2Fdocs%2Fa%20b%3Fx%3D1%26y", component);
+    /// ```
+    ///
+    pub fn percent_encode(&self, set: &EncodeSet, out: &mut impl Write) -> fmt::Result {
+        encode(self.as_str(), set, false, out)
+    }
+
+    /// decode `%XX` escapes, checking the result is valid UTF-8.
+    /// Errors give the position of the bad escape or of the first byte of the invalid sequence
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    ///
+    /// let b: BFRDYN<32> = "na%C3%AFve+100%25".into();
+    /// let mut out: BFRDYN<32> = BFRDYN::new();
+    /// b.percent_decode(&mut out).unwrap();
+    /// assert_eq!("naïve+100%", out.as_str());
+    ///
+    /// let bad: BFRDYN<32> = "ok%C3%28".into();
+    /// let e = bad.percent_decode(&mut String::new()).unwrap_err();
+    /// assert_eq!((2, "invalid utf-8"), (e.index(), e.reason()));
+    /// ```
+    ///
+    pub fn percent_decode(&self, out: &mut impl Write) -> Result<(), err::InvalidFormat> {
+        decode(self.as_str(), 0, false, out)
+    }
+}
+
+/// iterate the decoded `(key, value)` pairs of a query string (without the leading `?`),
+/// `+` is decoded as space, empty pairs are skipped and a pair without `=` has an empty value.
+/// Errors give the position in the query string
+/// # example
+/// ```
+/// use cbfr::BFRDYN;
+/// use cbfr::url;
+///
+/// let mut pairs = url::query_pairs::<16>("a=1&&flag&name=J%C3%BCrgen+K&bad=%zz");
+/// let (k, v) = pairs.next().unwrap().unwrap();
+/// assert_eq!(("a", "1"), (k.as_str(), v.as_str()));
+/// let (k, v) = pairs.next().unwrap().unwrap();
+/// assert_eq!(("flag", ""), (k.as_str(), v.as_str()));
+/// let (_, v) = pairs.next().unwrap().unwrap();
+/// assert_eq!("Jürgen K", v.as_str());
+/// assert_eq!(33, pairs.next().unwrap().unwrap_err().index());
+/// assert!(pairs.next().is_none());
+/// ```
+///
+pub fn query_pairs<const N: usize>(query: &str) -> QueryPairs<'_, N> {
+    QueryPairs { query, pos: 0 }
+}
+
+/// Iterator over decoded query string pairs, see [query_pairs]
+#[derive(Debug, Clone)]
+pub struct QueryPairs<'q, const N: usize> {
+    query: &'q str,
+    pos: usize,
+}
+
+impl<const N: usize> Iterator for QueryPairs<'_, N> {
+    type Item = Result<(BFRDYN<N>, BFRDYN<N>), err::InvalidFormat>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (start, pair) = loop {
+            if self.pos > self.query.len() { return None; }
+            let start = self.pos;
+            let end = self.query[start..].find('&').map_or(self.query.len(), |i| start + i);
+            self.pos = end + 1;
+            if end > start { break (start, &self.query[start..end]); }
+        };
+        let (key, value, value_at) = match pair.find('=') {
+            Some(eq) => (&pair[..eq], &pair[eq + 1..], start + eq + 1),
+            None => (pair, "", start + pair.len()),
+        };
+        let mut k = BFRDYN::<N>::new();
+        let mut v = BFRDYN::<N>::new();
+        let decoded = decode(key, start, true, &mut k).and_then(|_| decode(value, value_at, true, &mut v));
+        Some(decoded.map(|_| (k, v)))
+    }
+}
+
+/// Build a query string by appending encoded `key=value` pairs joined by `&`
+#[derive(Debug, Clone, Default)]
+pub struct QueryBuilder<const N: usize> {
+    buf: BFRDYN<N>,
+}
+
+impl<const N: usize> QueryBuilder<N> {
+    /// empty query string
+    pub fn new() -> Self { Self { buf: BFRDYN::new() } }
+
+    /// append `key=value`, both form encoded (space as `+`).
+    /// The query is unchanged when the pair doesn't fit
+    /// # example
+    /// ```
+    /// use cbfr::url::QueryBuilder;
+    ///
+    /// let mut q: QueryBuilder<16> = QueryBuilder::new();
+    /// q.append_pair("a b", "1/2").unwrap();
+    /// assert!(q.append_pair("long", "value that overflow").is_err());
+    /// assert_eq!("a+b=1%2F2", q.as_str());
+    /// ```
+    ///
+    pub fn append_pair(&mut self, key: &str, value: &str) -> NecResult {
+        let sep = !self.buf.is_empty() as usize;
+        let total = self.buf.len() + sep + encoded_len(key, &FORM, true) + 1 + encoded_len(value, &FORM, true);
+        if total > N {
+            return Err(err::NotEnoughCapacity::throw(N, total));
+        }
+        // fit was checked, writes can't fail
+        let buf = &mut self.buf;
+        let _ = (if sep == 1 { buf.write_char('&') } else { Ok(()) })
+            .and_then(|_| encode(key, &FORM, true, buf))
+            .and_then(|_| buf.write_char('='))
+            .and_then(|_| encode(value, &FORM, true, buf));
+        Ok(())
+    }
+
+    /// the query string built so far
+    pub fn as_str(&self) -> &str { self.buf.as_str() }
+
+    /// the buffer holding the query string
+    pub fn into_inner(self) -> BFRDYN<N> { self.buf }
+}
@@ -0,0 +1,102 @@
+//! Text width measured in chars or in terminal display columns.
+//! In columns East Asian wide and fullwidth chars (CJK, Hangul, most emoji) count as 2,
+//! combining marks, zero width chars and control chars count as 0, everything else as 1.
+//! # example
+//! ```
+//! use cbfr::width::{self, Width};
+//!
+//! assert_eq!(2, width::char_width('漢'));
+//! assert_eq!(0, width::char_width('\u{301}'));     // combining acute accent
+//! assert_eq!(7, width::str_width("日本 go"));
+//! assert_eq!(5, Width::Chars(0).measure("日本 go"));
+//! ```
+
+/// width unit for padding, truncating and wrapping.
+/// A plain `usize` convert to `Width::Chars`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Width {
+    /// number of chars
+    Chars(usize),
+    /// number of terminal display columns
+    Columns(usize),
+}
+
+impl Width {
+    /// the width, in its own unit
+    pub const fn value(&self) -> usize {
+        match self { Width::Chars(n) | Width::Columns(n) => *n }
+    }
+
+    /// width of a char in this unit
+    pub const fn measure_char(&self, c: char) -> usize {
+        match self {
+            Width::Chars(_) => 1,
+            Width::Columns(_) => char_width(c),
+        }
+    }
+
+    /// width of a text in this unit
+    pub fn measure(&self, s: &str) -> usize {
+        match self {
+            Width::Chars(_) => s.chars().count(),
+            Width::Columns(_) => str_width(s),
+        }
+    }
+}
+
+impl From<usize> for Width {
+    fn from(n: usize) -> Self { Width::Chars(n) }
+}
+
+/// zero width ranges: combining marks, zero width space/joiners, variation selectors
+const ZERO: [(u32, u32); 12] = [
+    (0x0300, 0x036F), (0x0483, 0x0489), (0x0591, 0x05BD), (0x0610, 0x061A),
+    (0x064B, 0x065F), (0x1AB0, 0x1AFF), (0x1DC0, 0x1DFF), (0x200B, 0x200F),
+    (0x20D0, 0x20FF), (0xFE00, 0xFE0F), (0xFE20, 0xFE2F), (0xE0100, 0xE01EF),
+];
+
+/// East Asian wide and fullwidth ranges (compact approximation of the Unicode tables)
+const WIDE: [(u32, u32); 54] = [
+    (0x1100, 0x115F), (0x231A, 0x231B), (0x2329, 0x232A), (0x23E9, 0x23EC),
+    (0x23F0, 0x23F0), (0x23F3, 0x23F3), (0x25FD, 0x25FE), (0x2614, 0x2615),
+    (0x2648, 0x2653), (0x267F, 0x267F), (0x2693, 0x2693), (0x26A1, 0x26A1),
+    (0x26AA, 0x26AB), (0x26BD, 0x26BE), (0x26C4, 0x26C5), (0x26CE, 0x26CE),
+    (0x26D4, 0x26D4), (0x26EA, 0x26EA), (0x26F2, 0x26F3), (0x26F5, 0x26F5),
+    (0x26FA, 0x26FA), (0x26FD, 0x26FD), (0x2705, 0x2705), (0x270A, 0x270B),
+    (0x2728, 0x2728), (0x274C, 0x274C), (0x274E, 0x274E), (0x2753, 0x2755),
+    (0x2757, 0x2757), (0x2795, 0x2797), (0x27B0, 0x27B0), (0x27BF, 0x27BF),
+    (0x2B1B, 0x2B1C), (0x2B50, 0x2B50), (0x2B55, 0x2B55), (0x2E80, 0x303E),
+    (0x3041, 0x33FF), (0x3400, 0x4DBF), (0x4E00, 0x9FFF), (0xA000, 0xA4CF),
+    (0xA960, 0xA97F), (0xAC00, 0xD7A3), (0xF900, 0xFAFF), (0xFE10, 0xFE19),
+    (0xFE30, 0xFE6F), (0xFF00, 0xFF60), (0xFFE0, 0xFFE6), (0x16FE0, 0x16FE4),
+    (0x17000, 0x18AFF), (0x1B000, 0x1B2FF), (0x1F004, 0x1F004), (0x1F0CF, 0x1F0CF),
+    (0x1F18E, 0x1F9FF), (0x20000, 0x3FFFD),
+];
+
+/// binary search a sorted range table
+const fn in_table(table: &[(u32, u32)], c: u32) -> bool {
+    let mut lo = 0;
+    let mut hi = table.len();
+    while lo < hi {
+        let mid = (lo + hi) / 2;
+        if c < table[mid].0 { hi = mid; }
+        else if c > table[mid].1 { lo = mid + 1; }
+        else { return true; }
+    }
+    false
+}
+
+/// display columns of a char: 0, 1 or 2
+pub const fn char_width(c: char) -> usize {
+    let c = c as u32;
+    if c < 0x20 || (c >= 0x7F && c < 0xA0) { return 0; }
+    if c < 0x300 { return 1; }
+    if in_table(&ZERO, c) { 0 }
+    else if in_table(&WIDE, c) { 2 }
+    else { 1 }
+}
+
+/// display columns of a text
+pub fn str_width(s: &str) -> usize {
+    s.chars().map(char_width).sum()
+}
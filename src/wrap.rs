@@ -0,0 +1,249 @@
+//! Word wrapping in display columns (see [width](crate::width)).
+//! [Wrap] hold the options, [Wrap::lines] iterate the wrapped lines as slices of the
+//! original text without any allocation, [BFRDYN::fill] rewrite a buffer with the newlines inserted.
+//!
+//! Lines break at spaces, and after a hyphen between 2 letters if asked.
+//! Spaces at a break are dropped, newlines already in the text are kept
+//! and start a new paragraph (which use the initial indent again).
+//! A word longer than the line is cut (`break_words(true)`, the default) or overflow.
+//! # example
+//! ```
+//! use cbfr::wrap::Wrap;
+//!
+//! let help = "Print the lines of every file matching the pattern";
+//! let opts = Wrap::new(20).initial_indent("* ").subsequent_indent("  ");
+//! let lines: Vec<String> = opts.lines(help).map(|l| l.to_string()).collect();
+//! assert_eq!(vec![
+//!     "* Print the lines of",
+//!     "  every file",
+//!     "  matching the",
+//!     "  pattern",
+//! ], lines);
+//! ```
+
+use std::fmt;
+
+use crate::cb::{BFRDYN, NecResult};
+use crate::width::char_width;
+
+/// Wrapping options, width is in display columns and include the indent
+#[derive(Debug, Clone, Copy)]
+pub struct Wrap<'a> {
+    width: usize,
+    initial_indent: &'a str,
+    subsequent_indent: &'a str,
+    break_words: bool,
+    break_on_hyphens: bool,
+}
+
+impl<'a> Wrap<'a> {
+    /// wrap at `width` columns, no indent, long words are cut, no hyphen breaks
+    pub const fn new(width: usize) -> Self {
+        Self {
+            width,
+            initial_indent: "",
+            subsequent_indent: "",
+            break_words: true,
+            break_on_hyphens: false,
+        }
+    }
+
+    /// indent of the first line of each paragraph
+    pub const fn initial_indent(mut self, indent: &'a str) -> Self {
+        self.initial_indent = indent;
+        self
+    }
+
+    /// indent of the other lines (hanging indent)
+    pub const fn subsequent_indent(mut self, indent: &'a str) -> Self {
+        self.subsequent_indent = indent;
+        self
+    }
+
+    /// cut words longer than the line (true) or let them overflow (false)
+    /// # example
+    /// ```
+    /// use cbfr::wrap::Wrap;
+    ///
+    /// let url = "see https://example.com/a/long/path";
+    /// let cut: Vec<&str> = Wrap::new(12).lines(url).map(|l| l.text()).collect();
+    /// assert_eq!(vec!["see", "https://exam", "ple.com/a/lo", "ng/path"], cut);
+    ///
+    /// let keep: Vec<&str> = Wrap::new(12).break_words(false).lines(url).map(|l| l.text()).collect();
+    /// assert_eq!(vec!["see", "https://example.com/a/long/path"], keep);
+    /// ```
+    ///
+    pub const fn break_words(mut self, yes: bool) -> Self {
+        self.break_words = yes;
+        self
+    }
+
+    /// allow a break right after a hyphen between 2 letters or digits
+    /// # example
+    /// ```
+    /// use cbfr::wrap::Wrap;
+    ///
+    /// let text = "a well-known fact";
+    /// let lines: Vec<&str> = Wrap::new(8).break_on_hyphens(true).lines(text).map(|l| l.text()).collect();
+    /// assert_eq!(vec!["a well-", "known", "fact"], lines);
+    /// ```
+    ///
+    pub const fn break_on_hyphens(mut self, yes: bool) -> Self {
+        self.break_on_hyphens = yes;
+        self
+    }
+
+    /// iterate the wrapped lines of a text, without allocation.
+    /// Leading blanks of a paragraph are kept as part of its first line
+    /// # example
+    /// ```
+    /// use cbfr::wrap::Wrap;
+    ///
+    /// let lines: Vec<&str> = Wrap::new(5).lines("  ab cd ef").map(|l| l.text()).collect();
+    /// assert_eq!(vec!["  ab", "cd ef"], lines);
+    ///
+    /// let lines: Vec<&str> = Wrap::new(3).lines("  ab cd").map(|l| l.text()).collect();
+    /// assert_eq!(vec!["  a", "b", "cd"], lines);
+    ///
+    /// let lines: Vec<&str> = Wrap::new(5).break_words(false).lines("   verylongword x").map(|l| l.text()).collect();
+    /// assert_eq!(vec!["   verylongword", "x"], lines);
+    /// ```
+    ///
+    pub fn lines<'t>(&self, text: &'t str) -> Lines<'a, 't> {
+        Lines { opts: *self, text, pos: 0, paragraph_start: true }
+    }
+}
+
+/// One wrapped line: its indent and a slice of the original text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Line<'a, 't> {
+    indent: &'a str,
+    text: &'t str,
+}
+
+impl<'a, 't> Line<'a, 't> {
+    /// indent to print before the text
+    pub const fn indent(&self) -> &'a str { self.indent }
+
+    /// the text of the line, a slice of the wrapped text
+    pub const fn text(&self) -> &'t str { self.text }
+}
+
+impl fmt::Display for Line<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.indent, self.text)
+    }
+}
+
+/// Iterator over wrapped lines, see [Wrap::lines]
+#[derive(Debug, Clone)]
+pub struct Lines<'a, 't> {
+    opts: Wrap<'a>,
+    text: &'t str,
+    pos: usize,
+    paragraph_start: bool,
+}
+
+fn is_blank(c: char) -> bool { c == ' ' || c == '\t' }
+
+impl<'a, 't> Iterator for Lines<'a, 't> {
+    type Item = Line<'a, 't>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let text = self.text;
+        let indent = if self.paragraph_start { self.opts.initial_indent } else { self.opts.subsequent_indent };
+        if !self.paragraph_start {
+            self.pos = text[self.pos..].find(|c| !is_blank(c)).map_or(text.len(), |i| self.pos + i);
+        }
+        if self.pos >= text.len() { return None; }
+        let start = self.pos;
+        let avail = self.opts.width.saturating_sub(crate::width::str_width(indent)).max(1);
+
+        let mut used = 0;
+        // end of the line and where the next one start at the last break opportunity
+        let mut brk: Option<(usize, usize)> = None;
+        let mut prev = '\n';
+        let mut chars = text[start..].char_indices().map(|(i, c)| (start + i, c)).peekable();
+        let (end, resume, hard) = loop {
+            let Some((i, c)) = chars.next() else { break (text.len(), text.len(), false) };
+            if c == '\n' { break (i, i + 1, true); }
+            used += char_width(c);
+            if is_blank(c) {
+                // leading blanks of an indented paragraph are not a break opportunity
+                if !is_blank(prev) && i > start { brk = Some((i, i)); }
+            } else if used > avail {
+                if let Some((end, resume)) = brk { break (end, resume, false); }
+                if self.opts.break_words {
+                    // keep at least one non blank char so the iteration always progress
+                    let cut = if text[start..i].chars().all(is_blank) { i + c.len_utf8() } else { i };
+                    break (cut, cut, false);
+                }
+                let end = text[i..].find(char::is_whitespace).map_or(text.len(), |e| i + e);
+                break (end, end, false);
+            } else if self.opts.break_on_hyphens && c == '-' && prev.is_alphanumeric()
+                && chars.peek().is_some_and(|(_, n)| n.is_alphanumeric()) {
+                brk = Some((i + 1, i + 1));
+            }
+            prev = c;
+        };
+        self.pos = resume;
+        self.paragraph_start = hard;
+        Some(Line { indent, text: text[start..end].trim_end_matches(is_blank) })
+    }
+}
+
+impl<const CAPACITY: usize> BFRDYN<CAPACITY> {
+    /// iterate the lines of the buffer wrapped at `width` display columns
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    ///
+    /// let b: BFRDYN<64> = "the quick brown fox jumps".into();
+    /// let lines: Vec<&str> = b.wrap(10).map(|l| l.text()).collect();
+    /// assert_eq!(vec!["the quick", "brown fox", "jumps"], lines);
+    /// ```
+    ///
+    pub fn wrap(&self, width: usize) -> Lines<'static, '_> {
+        Wrap::new(width).lines(self.as_str())
+    }
+
+    /// rewrite the buffer wrapped at `width` display columns, lines separated by '\n'
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    ///
+    /// let mut b: BFRDYN<64> = "the quick brown fox jumps".into();
+    /// b.fill(10).unwrap();
+    /// assert_eq!("the quick\nbrown fox\njumps", b.as_str());
+    /// ```
+    ///
+    pub fn fill(&mut self, width: usize) -> NecResult {
+        self.fill_with(&Wrap::new(width))
+    }
+
+    /// rewrite the buffer wrapped with options, the buffer is unchanged on error
+    /// # example
+    /// ```
+    /// use cbfr::BFRDYN;
+    /// use cbfr::wrap::Wrap;
+    ///
+    /// let mut b: BFRDYN<64> = "-v  print more details about each step".into();
+    /// b.fill_with(&Wrap::new(16).subsequent_indent("    ")).unwrap();
+    /// assert_eq!("-v  print more\n    details\n    about each\n    step", b.as_str());
+    ///
+    /// let mut small: BFRDYN<8> = "aaaa bbb".into();
+    /// assert!(small.fill_with(&Wrap::new(4).subsequent_indent("  ")).is_err());
+    /// assert_eq!("aaaa bbb", small.as_str());
+    /// ```
+    ///
+    pub fn fill_with(&mut self, opts: &Wrap) -> NecResult {
+        let mut out = Self::new();
+        for (n, line) in opts.lines(self.as_str()).enumerate() {
+            if n > 0 { out.append_str("\n")?; }
+            out.append_str(line.indent())?;
+            out.append_str(line.text())?;
+        }
+        *self = out;
+        Ok(())
+    }
+}
@@ -0,0 +1,72 @@
+//! Check every vector path of cbfr::scan against the scalar path
+//! for every length up to 256 bytes at every alignment of the widest (32 byte) vector.
+
+use cbfr::scan::{self, scalar};
+
+const MAX_LEN: usize = 256;
+const MAX_ALIGN: usize = 32;
+
+/// buffer with a few repeating byte values so every needle show up at many positions
+fn pattern(seed: usize) -> Vec<u8> {
+    (0..MAX_LEN + MAX_ALIGN)
+        .map(|i| b"ab  \n,x"[(i * 7 + seed * 13 + i / 5) % 7])
+        .collect()
+}
+
+/// call `check` on every (length, alignment) window of a few patterns
+fn windows(mut check: impl FnMut(&[u8])) {
+    for seed in 0..3 {
+        let buf = pattern(seed);
+        for align in 0..MAX_ALIGN {
+            for len in 0..=MAX_LEN {
+                check(&buf[align..align + len]);
+            }
+        }
+    }
+    // single needle at every position of an otherwise empty buffer
+    for len in 0..=MAX_LEN {
+        for at in 0..len {
+            let mut buf = vec![b'.'; len];
+            buf[at] = b',';
+            check(&buf);
+        }
+    }
+}
+
+/// run every routine of one implementation and compare with scalar
+macro_rules! compare {
+    ($h:expr, $imp:path) => {{
+        use $imp as imp;
+        let h = $h;
+        for n in [b'a', b' ', b',', b'\n', b'z'] {
+            assert_eq!(scalar::find_byte(h, n), imp::find_byte(h, n), "find_byte {n} in {h:?}");
+            assert_eq!(scalar::rfind_byte(h, n), imp::rfind_byte(h, n), "rfind_byte {n} in {h:?}");
+            assert_eq!(scalar::find_not_byte(h, n), imp::find_not_byte(h, n), "find_not_byte {n} in {h:?}");
+            assert_eq!(scalar::rfind_not_byte(h, n), imp::rfind_not_byte(h, n), "rfind_not_byte {n} in {h:?}");
+            assert_eq!(scalar::count_byte(h, n), imp::count_byte(h, n), "count_byte {n} in {h:?}");
+            assert_eq!(scalar::find_any_of_2(h, n, b'x'), imp::find_any_of_2(h, n, b'x'), "find_any_of_2 {n} in {h:?}");
+            assert_eq!(scalar::find_any_of_3(h, b'z', n, b'\n'), imp::find_any_of_3(h, b'z', n, b'\n'), "find_any_of_3 {n} in {h:?}");
+        }
+    }};
+}
+
+#[test]
+fn dispatch_match_scalar() {
+    windows(|h| compare!(h, scan));
+}
+
+#[cfg(target_arch = "x86_64")]
+#[test]
+fn sse2_match_scalar() {
+    windows(|h| compare!(h, scan::forced::sse2));
+}
+
+#[cfg(target_arch = "x86_64")]
+#[test]
+fn avx2_match_scalar() {
+    if !std::is_x86_feature_detected!("avx2") {
+        eprintln!("avx2 not supported on this cpu, skipped");
+        return;
+    }
+    windows(|h| compare!(h, scan::forced::avx2));
+}